@@ -0,0 +1,748 @@
+use std::{collections::HashMap, mem::discriminant};
+
+use crate::{
+    classifier::{atoms, Atom},
+    diagnostic::Diagnostic,
+    hierarchy::{Hierarchy, Node},
+    interval::{self, Interval},
+    normalize,
+    parser::{
+        Class, ClassDecl, Data, DataDescription, Frame, ObjectDescription, Ontology, Property,
+        PropertyDescription,
+    },
+    span::{Located, Span},
+    table::SymbolTable,
+};
+
+/// Runs every semantic check over a parsed ontology, with the `Range:`s
+/// declared in `ontologies`, every ontology loaded with it.
+pub fn check<'o>(
+    ontology: &Ontology,
+    ontologies: impl IntoIterator<Item = &'o Ontology>,
+    table: &SymbolTable,
+) -> Vec<Diagnostic> {
+    let mut diagnostics = Vec::new();
+
+    let lists = restriction_lists(ontology);
+    let ranges = declared_ranges(ontologies);
+
+    for properties in &lists {
+        check_repetitions(properties, table, &mut diagnostics);
+        check_closures(properties, table, &mut diagnostics);
+        check_cardinalities(properties, table, &mut diagnostics);
+    }
+
+    check_values(&lists, &ranges, table, &mut diagnostics);
+    check_data_ranges(ontology, &lists, &ranges, table, &mut diagnostics);
+
+    diagnostics
+}
+
+/// The `Range:`s declared in every ontology, each with the index of its
+/// property, as a property is restricted by them in every file it is used.
+fn declared_ranges<'o>(
+    ontologies: impl IntoIterator<Item = &'o Ontology>,
+) -> Vec<(usize, &'o Located<Data>)> {
+    ontologies
+        .into_iter()
+        .flat_map(|ontology| &ontology.frames)
+        .filter_map(|frame| match &frame.value {
+            Frame::DataProperty {
+                property, ranges, ..
+            } => Some(ranges.iter().map(|range| (property.index, range))),
+            _ => None,
+        })
+        .flatten()
+        .collect()
+}
+
+/// Every list of restrictions on the same class, including the ones of
+/// nested descriptions.
+fn restriction_lists(ontology: &Ontology) -> Vec<&[Located<Property>]> {
+    let mut lists = Vec::new();
+
+    for frame in &ontology.frames {
+        match &frame.value {
+            Frame::Decl { decl, .. } => match &decl.value {
+                ClassDecl::Defined(_, properties) | ClassDecl::Primitive(_, properties) => {
+                    push_restrictions(properties, &mut lists);
+                }
+                ClassDecl::Enumerated(_) | ClassDecl::Union(_) => {}
+            },
+            Frame::EquivalentClasses(misc) | Frame::DisjointClasses(misc) => {
+                for class in &misc.members {
+                    if let Class::Defined(_, properties) = class {
+                        push_restrictions(properties, &mut lists);
+                    }
+                }
+            }
+            Frame::Class { super_classes, .. } => {
+                for class in super_classes {
+                    if let Class::Defined(_, properties) = &class.value {
+                        push_restrictions(properties, &mut lists);
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    lists
+}
+
+fn push_restrictions<'a>(
+    properties: &'a [Located<Property>],
+    lists: &mut Vec<&'a [Located<Property>]>,
+) {
+    lists.push(properties);
+
+    for property in properties {
+        if let PropertyDescription::Object(
+            ObjectDescription::Some(Class::Defined(_, nested))
+            | ObjectDescription::Only(Class::Defined(_, nested))
+            | ObjectDescription::Min(_, Class::Defined(_, nested))
+            | ObjectDescription::Max(_, Class::Defined(_, nested))
+            | ObjectDescription::Exactly(_, Class::Defined(_, nested)),
+        ) = &property.value.description
+        {
+            push_restrictions(nested, lists);
+        }
+    }
+}
+
+/// Restrictions grouped by property, in order of first appearance.
+fn by_property(properties: &[Located<Property>]) -> Vec<Vec<&Located<Property>>> {
+    let mut groups: Vec<Vec<&Located<Property>>> = Vec::new();
+
+    for property in properties {
+        match groups
+            .iter_mut()
+            .find(|group| group[0].value.index == property.value.index)
+        {
+            Some(group) => group.push(property),
+            None => groups.push(vec![property]),
+        }
+    }
+
+    groups
+}
+
+/// Warns about a restriction repeated within the same description.
+fn check_repetitions(
+    properties: &[Located<Property>],
+    table: &SymbolTable,
+    diagnostics: &mut Vec<Diagnostic>,
+) {
+    for (i, property) in properties.iter().enumerate() {
+        if let Some(first) = properties[..i].iter().find(|p| p.value == property.value) {
+            let name = table.get(property.value.index).map_or("?", |s| s.id());
+            let message = format!("restriction on '{}' is repeated", name);
+            diagnostics.push(
+                Diagnostic::warning(property.span, message)
+                    .with_related(first.span, "first occurrence"),
+            );
+        }
+    }
+}
+
+/// Warns when the `ONLY` restriction closing a property does not match the
+/// fillers of its `SOME` restrictions, in either direction.
+fn check_closures(
+    properties: &[Located<Property>],
+    table: &SymbolTable,
+    diagnostics: &mut Vec<Diagnostic>,
+) {
+    for restrictions in by_property(properties) {
+        let existentials = restrictions
+            .iter()
+            .filter_map(|p| match &p.value.description {
+                PropertyDescription::Object(ObjectDescription::Some(class)) => {
+                    Some((*p, atoms(class)))
+                }
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+
+        let universals = restrictions
+            .iter()
+            .filter_map(|p| match &p.value.description {
+                PropertyDescription::Object(ObjectDescription::Only(class)) => {
+                    Some((*p, atoms(class)))
+                }
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+
+        // a lone SOME or a lone ONLY is not an attempt at a closure
+        if existentials.is_empty() {
+            continue;
+        }
+
+        let name = table
+            .get(restrictions[0].value.index)
+            .map_or("?", |s| s.id());
+
+        for (only, closed) in &universals {
+            for (some, fillers) in &existentials {
+                for atom in fillers.iter().filter(|atom| !closed.contains(atom)) {
+                    let message = format!(
+                        "{} is used in a SOME restriction on '{}' but is missing from its ONLY closure",
+                        describe(atom, table),
+                        name
+                    );
+                    diagnostics.push(
+                        Diagnostic::warning(some.span, message).with_related(only.span, "closure"),
+                    );
+                }
+            }
+
+            for atom in closed
+                .iter()
+                .filter(|atom| !existentials.iter().any(|(_, f)| f.contains(atom)))
+            {
+                let message = format!(
+                    "{} is allowed by the ONLY closure on '{}' but no SOME restriction uses it",
+                    describe(atom, table),
+                    name
+                );
+                diagnostics.push(Diagnostic::warning(only.span, message));
+            }
+        }
+    }
+}
+
+// what a restriction counts, so only restrictions on the same filler are
+// compared with each other
+#[derive(PartialEq)]
+enum Filler<'a> {
+    Class(&'a Class),
+    Data(&'a Data),
+}
+
+/// Bounds a restriction puts on the number of values of its property:
+/// `(filler, at least, at most)`.
+fn bounds(property: &Property) -> Option<(Filler<'_>, usize, Option<usize>)> {
+    match &property.description {
+        PropertyDescription::Object(description) => match description {
+            ObjectDescription::Some(class) => Some((Filler::Class(class), 1, None)),
+            ObjectDescription::Min(n, class) => Some((Filler::Class(class), *n, None)),
+            ObjectDescription::Max(n, class) => Some((Filler::Class(class), 0, Some(*n))),
+            ObjectDescription::Exactly(n, class) => Some((Filler::Class(class), *n, Some(*n))),
+            ObjectDescription::Only(_) | ObjectDescription::Value(_) => None,
+        },
+        PropertyDescription::Data(description) => match description {
+            DataDescription::Some(data) => Some((Filler::Data(data), 1, None)),
+            DataDescription::Min(n, data) => Some((Filler::Data(data), *n, None)),
+            DataDescription::Max(n, data) => Some((Filler::Data(data), 0, Some(*n))),
+            DataDescription::Exactly(n, data) => Some((Filler::Data(data), *n, Some(*n))),
+            DataDescription::Only(_) | DataDescription::Value(_) => None,
+        },
+    }
+}
+
+/// Warns about `MIN 0`, which restricts nothing, and about restrictions on
+/// the same property and filler whose bounds cannot all hold, such as
+/// `MIN 5` with `MAX 3` or `SOME` with `MAX 0`.
+fn check_cardinalities(
+    properties: &[Located<Property>],
+    table: &SymbolTable,
+    diagnostics: &mut Vec<Diagnostic>,
+) {
+    for restrictions in by_property(properties) {
+        let name = table
+            .get(restrictions[0].value.index)
+            .map_or("?", |s| s.id());
+
+        let bounded = restrictions
+            .iter()
+            .filter_map(|p| bounds(&p.value).map(|bounds| (*p, bounds)))
+            .collect::<Vec<_>>();
+
+        for (i, (property, (filler, min, max))) in bounded.iter().enumerate() {
+            if matches!(
+                property.value.description,
+                PropertyDescription::Object(ObjectDescription::Min(0, _))
+                    | PropertyDescription::Data(DataDescription::Min(0, _))
+            ) {
+                let message = format!("MIN 0 on '{}' is always satisfied", name);
+                diagnostics.push(Diagnostic::warning(property.span, message));
+            }
+
+            for (previous, (other, other_min, other_max)) in &bounded[..i] {
+                if filler != other {
+                    continue;
+                }
+
+                let conflict = match (max, other_max) {
+                    (_, Some(other_max)) if min > other_max => Some((*min, *other_max)),
+                    (Some(max), _) if other_min > max => Some((*other_min, *max)),
+                    _ => None,
+                };
+
+                if let Some((min, max)) = conflict {
+                    let message = format!(
+                        "restrictions on '{}' require at least {} but at most {} values",
+                        name, min, max
+                    );
+                    diagnostics.push(
+                        Diagnostic::warning(property.span, message)
+                            .with_related(previous.span, "conflicting restriction"),
+                    );
+                }
+            }
+        }
+    }
+}
+
+/// Checks the literals of `VALUE` restrictions against the datatype of
+/// their property, when the `Range:` declared for it and every other
+/// restriction on it agree on one.
+fn check_values(
+    lists: &[&[Located<Property>]],
+    declared: &[(usize, &Located<Data>)],
+    table: &SymbolTable,
+    diagnostics: &mut Vec<Diagnostic>,
+) {
+    let restrictions = lists
+        .iter()
+        .flat_map(|list| list.iter())
+        .collect::<Vec<_>>();
+
+    for property in &restrictions {
+        let PropertyDescription::Data(DataDescription::Value(literal)) =
+            &property.value.description
+        else {
+            continue;
+        };
+
+        let ranges = declared
+            .iter()
+            .filter(|(index, _)| *index == property.value.index)
+            .map(|(_, range)| (range.span, range.value.datatype));
+
+        let mut datatypes = ranges.chain(restrictions.iter().filter_map(|p| {
+            if p.value.index != property.value.index {
+                return None;
+            }
+
+            match &p.value.description {
+                PropertyDescription::Data(
+                    DataDescription::Some(data)
+                    | DataDescription::Only(data)
+                    | DataDescription::Min(_, data)
+                    | DataDescription::Max(_, data)
+                    | DataDescription::Exactly(_, data),
+                ) => Some((p.span, data.datatype)),
+                _ => None,
+            }
+        }));
+
+        let Some((typed, datatype)) = datatypes.next() else {
+            continue;
+        };
+
+        if datatypes.any(|(_, other)| other != datatype) {
+            continue;
+        }
+
+        let lexical = table.get(literal.value.index).map_or("", |s| s.id());
+
+        if !datatype.accepts(lexical) {
+            let name = table.get(property.value.index).map_or("?", |s| s.id());
+            let message = format!("value of '{}' is not a valid {:?}", name, datatype);
+            diagnostics.push(
+                Diagnostic::warning(literal.span, message).with_related(typed, "datatype given"),
+            );
+        }
+    }
+}
+
+/// Warns about data ranges that no value can satisfy, intersecting the
+/// `Range:` declared for a property with its `ONLY` restrictions, and
+/// those with each `SOME`, `MIN`, `EXACTLY` and `VALUE` on it.
+fn check_data_ranges(
+    ontology: &Ontology,
+    lists: &[&[Located<Property>]],
+    declared: &[(usize, &Located<Data>)],
+    table: &SymbolTable,
+    diagnostics: &mut Vec<Diagnostic>,
+) {
+    let local = declared_ranges([ontology]);
+
+    // `Range:`s that cannot hold together are reported in the file of the
+    // later one, against the ones declared before it in any file
+    for (i, &(index, range)) in declared.iter().enumerate() {
+        if !local.iter().any(|(_, own)| std::ptr::eq(*own, range)) {
+            continue;
+        }
+
+        let name = table.get(index).map_or("?", |s| s.id());
+
+        let constraints = declared[..i]
+            .iter()
+            .filter(|(other, _)| *other == index)
+            .map(|(_, range)| (range.span, &range.value));
+
+        if let Some(diagnostic) = empty_range(name, range.span, &range.value, constraints, table) {
+            diagnostics.push(diagnostic);
+        }
+    }
+
+    for properties in lists {
+        for restrictions in by_property(properties) {
+            let index = restrictions[0].value.index;
+            let name = table.get(index).map_or("?", |s| s.id());
+
+            // data ranges every value of the property must be in
+            let mut constraints = declared
+                .iter()
+                .filter(|(i, _)| *i == index)
+                .map(|(_, range)| (range.span, &range.value))
+                .collect::<Vec<_>>();
+
+            for property in &restrictions {
+                if let PropertyDescription::Data(DataDescription::Only(data)) =
+                    &property.value.description
+                {
+                    match empty_range(
+                        name,
+                        property.span,
+                        data,
+                        constraints.iter().copied(),
+                        table,
+                    ) {
+                        Some(diagnostic) => diagnostics.push(diagnostic),
+                        None => constraints.push((property.span, data)),
+                    }
+                }
+            }
+
+            for property in &restrictions {
+                match &property.value.description {
+                    PropertyDescription::Data(
+                        DataDescription::Some(data)
+                        | DataDescription::Min(1.., data)
+                        | DataDescription::Exactly(1.., data),
+                    ) => {
+                        let constraints = constraints.iter().copied();
+                        if let Some(diagnostic) =
+                            empty_range(name, property.span, data, constraints, table)
+                        {
+                            diagnostics.push(diagnostic);
+                        }
+                    }
+                    PropertyDescription::Data(DataDescription::Value(literal)) => {
+                        let Some(value) = interval::number(&literal.value, table) else {
+                            continue;
+                        };
+
+                        for (span, data) in &constraints {
+                            if Interval::of(data, table).is_some_and(|i| !i.contains(value)) {
+                                let message =
+                                    format!("value of '{}' is outside its data range", name);
+                                diagnostics.push(
+                                    Diagnostic::warning(literal.span, message)
+                                        .with_related(*span, "data range"),
+                                );
+                                break;
+                            }
+                        }
+                    }
+                    _ => {}
+                }
+            }
+        }
+    }
+}
+
+/// A warning at `span` if `data` has no value in common with the other
+/// data ranges of the property.
+fn empty_range<'a>(
+    name: &str,
+    span: Span,
+    data: &Data,
+    constraints: impl Iterator<Item = (Span, &'a Data)>,
+    table: &SymbolTable,
+) -> Option<Diagnostic> {
+    let mut interval = Interval::of(data, table)?;
+    let mut related = Vec::new();
+
+    for (other_span, other) in constraints {
+        let Some(other) = Interval::of(other, table) else {
+            continue;
+        };
+
+        interval = interval.intersect(&other);
+        related.push(other_span);
+
+        if interval.is_empty() {
+            let message = format!("no value of '{}' satisfies this data range", name);
+            let diagnostic = related
+                .into_iter()
+                .fold(Diagnostic::warning(span, message), |diagnostic, span| {
+                    diagnostic.with_related(span, "intersected with")
+                });
+            return Some(diagnostic);
+        }
+    }
+
+    None
+}
+
+/// Reports every cycle of subclass axioms in the told hierarchy with the
+/// chain of axioms that create it, unless its classes are explicitly
+/// equivalent, and every class used inside the restrictions of its own
+/// description with the restrictions nested on the way.
+pub fn check_cycles(hierarchy: &Hierarchy, table: &SymbolTable) -> Vec<Diagnostic> {
+    let name = |node: Node| match node {
+        Node::Named(index) => table.get(index).map_or("?", |s| s.id()).to_string(),
+        Node::Anonymous(_) => "an anonymous class".to_string(),
+    };
+
+    let self_references = hierarchy.self_references().iter().map(|(class, path)| {
+        let properties = path
+            .iter()
+            .map(|(property, _)| format!("'{}'", table.get(*property).map_or("?", |s| s.id())))
+            .collect::<Vec<_>>();
+        let message = format!(
+            "class '{}' is defined in terms of itself through {}",
+            name(Node::Named(*class)),
+            properties.join(", ")
+        );
+
+        path[1..].iter().fold(
+            Diagnostic::warning(path[0].1, message),
+            |diagnostic, (_, span)| diagnostic.with_related(*span, "nested restriction"),
+        )
+    });
+
+    hierarchy
+        .cycles()
+        .into_iter()
+        .map(|cycle| {
+            let (first, _, span) = cycle[0];
+
+            if cycle.len() == 1 {
+                let message = format!("class '{}' is defined in terms of itself", name(first));
+                return Diagnostic::warning(span, message);
+            }
+
+            let chain = cycle
+                .iter()
+                .map(|(child, _, _)| name(*child))
+                .chain([name(first)])
+                .collect::<Vec<_>>();
+            let message = format!("cycle in the class hierarchy: {}", chain.join(" < "));
+
+            cycle.iter().fold(
+                Diagnostic::warning(span, message),
+                |diagnostic, (child, parent, span)| {
+                    let note = format!("'{}' is below '{}'", name(*child), name(*parent));
+                    diagnostic.with_related(*span, &note)
+                },
+            )
+        })
+        .chain(self_references)
+        .collect()
+}
+
+fn describe(atom: &Atom, table: &SymbolTable) -> String {
+    match atom {
+        Atom::Named(class) => {
+            let name = table.get(class.index).map_or("?", |s| s.id());
+            format!("'{}'", name)
+        }
+        Atom::Anonymous(_) => "an anonymous description".to_string(),
+    }
+}
+
+/// Reports declarations that repeat an earlier one, verbatim or up to the
+/// order and grouping of their operands, by hashing their normalized form.
+pub fn check_duplicates<'o>(ontologies: impl IntoIterator<Item = &'o Ontology>) -> Vec<Diagnostic> {
+    let mut diagnostics = Vec::new();
+    let mut seen: HashMap<_, &Located<ClassDecl>> = HashMap::new();
+
+    let decls = ontologies
+        .into_iter()
+        .flat_map(|ontology| &ontology.frames)
+        .filter_map(|frame| match &frame.value {
+            Frame::Decl { decl, .. } => Some(decl),
+            _ => None,
+        });
+
+    for decl in decls {
+        // a definition and a primitive class with the same description
+        // are different axioms
+        let key = (discriminant(&decl.value), normalize::decl(&decl.value));
+
+        match seen.get(&key) {
+            Some(&first) => {
+                let message = if first.value == decl.value {
+                    "declaration repeats an earlier one"
+                } else {
+                    "declaration is equivalent to an earlier one up to the order of its operands"
+                };
+                diagnostics.push(
+                    Diagnostic::warning(decl.span, message.to_string())
+                        .with_related(first.span, "earlier declaration"),
+                );
+            }
+            None => {
+                seen.insert(key, decl);
+            }
+        }
+    }
+
+    diagnostics
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{lexer, parser, span::SourceMap};
+
+    // files parsed into one symbol table
+    fn parse_all(texts: &[&str]) -> (SymbolTable, Vec<Ontology>) {
+        let mut map = SourceMap::new();
+        let mut table = SymbolTable::new();
+        let mut ontologies = Vec::new();
+
+        for text in texts {
+            let file_id = map.add("test.txt".to_string(), text.to_string());
+
+            let Ok(tokens) = lexer::parse(&map, file_id, &mut table) else {
+                panic!("could not lex {:?}", text);
+            };
+            let Ok(ontology) = parser::parse(&tokens, &mut table) else {
+                panic!("could not parse {:?}", text);
+            };
+
+            ontologies.push(ontology);
+        }
+
+        (table, ontologies)
+    }
+
+    // the messages of the checks of files sharing one symbol table
+    fn messages_across(texts: &[&str]) -> Vec<String> {
+        let (table, ontologies) = parse_all(texts);
+
+        ontologies
+            .iter()
+            .flat_map(|ontology| check(ontology, &ontologies, &table))
+            .map(|diagnostic| diagnostic.message)
+            .collect()
+    }
+
+    fn messages(text: &str) -> Vec<String> {
+        messages_across(&[text])
+    }
+
+    #[test]
+    fn min_above_max_conflicts() {
+        assert_eq!(
+            messages("Pizza AND (hasTopping MIN 5 Cheese) AND (hasTopping MAX 3 Cheese)\n"),
+            ["restrictions on 'hasTopping' require at least 5 but at most 3 values"]
+        );
+    }
+
+    #[test]
+    fn bounds_on_other_fillers_do_not_conflict() {
+        assert!(messages(
+            "Pizza AND (hasTopping MIN 5 Cheese) AND (hasTopping MAX 3 Ham) \
+             AND (hasTopping MIN 3 Ham)\n"
+        )
+        .is_empty());
+    }
+
+    #[test]
+    fn min_zero_is_vacuous() {
+        assert_eq!(
+            messages("Pizza AND (hasTopping MIN 0 Cheese)\n"),
+            ["MIN 0 on 'hasTopping' is always satisfied"]
+        );
+    }
+
+    #[test]
+    fn some_conflicts_with_max_zero() {
+        assert_eq!(
+            messages("Pizza AND (hasTopping SOME Cheese) AND (hasTopping MAX 0 Cheese)\n"),
+            ["restrictions on 'hasTopping' require at least 1 but at most 0 values"]
+        );
+    }
+
+    #[test]
+    fn declared_ranges_apply_in_other_files() {
+        let declaration = "DataProperty: hasCalories\n    Range: integer[>=\"0\"]\n";
+        let pizza = "Pizza AND (hasCalories SOME integer[<\"0\"]) AND (hasCalories VALUE \"-5\")\n";
+
+        assert_eq!(
+            messages_across(&[declaration, pizza]),
+            [
+                "no value of 'hasCalories' satisfies this data range",
+                "value of 'hasCalories' is outside its data range",
+            ]
+        );
+    }
+
+    #[test]
+    fn conflicting_ranges_are_reported_once() {
+        let first = "DataProperty: hasCalories\n    Range: integer[>\"10\"]\n";
+        let second = "DataProperty: hasCalories\n    Range: integer[<\"5\"]\n";
+
+        assert_eq!(
+            messages_across(&[first, second]),
+            ["no value of 'hasCalories' satisfies this data range"]
+        );
+    }
+
+    #[test]
+    fn declarations_defined_in_terms_of_themselves_are_reported() {
+        let (table, ontologies) =
+            parse_all(&["Pizza AND (hasBase SOME (Base AND (isBaseOf SOME Pizza)))
+"]);
+        let hierarchy = Hierarchy::new(&ontologies);
+
+        let messages = check_cycles(&hierarchy, &table)
+            .into_iter()
+            .map(|diagnostic| diagnostic.message)
+            .collect::<Vec<_>>();
+        assert_eq!(
+            messages,
+            ["class 'Pizza' is defined in terms of itself through 'hasBase', 'isBaseOf'"]
+        );
+    }
+
+    fn duplicates(text: &str) -> Vec<String> {
+        let (_, ontologies) = parse_all(&[text]);
+
+        check_duplicates(&ontologies)
+            .into_iter()
+            .map(|diagnostic| diagnostic.message)
+            .collect()
+    }
+
+    #[test]
+    fn reordered_duplicates_are_reported() {
+        assert_eq!(
+            duplicates(
+                "Pizza AND (hasTopping SOME Cheese) AND (hasBase SOME Thin)\n\
+                 Pizza AND (hasBase SOME Thin) AND (hasTopping SOME Cheese)\n\
+                 Pizza AND (hasTopping SOME Cheese) AND (hasBase SOME Thin)\n"
+            ),
+            [
+                "declaration is equivalent to an earlier one up to the order of its operands",
+                "declaration repeats an earlier one",
+            ]
+        );
+    }
+
+    #[test]
+    fn primitive_and_defined_declarations_are_not_duplicates() {
+        assert!(duplicates(
+            "Pizza AND (hasTopping SOME Cheese)\n\
+             Pizza hasTopping SOME Cheese\n"
+        )
+        .is_empty());
+    }
+}
@@ -0,0 +1,239 @@
+use crate::{
+    parser::{
+        Class, ClassDecl, ClassIdentifier, DataDescription, ObjectDescription, Property,
+        PropertyDescription,
+    },
+    span::Located,
+};
+
+/// Modelling category of a class declaration, beyond its surface syntax.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Label {
+    /// Necessary conditions only: `A p SOME B`.
+    Primitive,
+    /// Necessary and sufficient conditions: `A AND (p SOME B)`.
+    Defined,
+    /// `SOME` restrictions on a property closed by an `ONLY` over the
+    /// union of their fillers.
+    Closure,
+    /// `A OR B OR ...`, covering a class by its subclasses.
+    Covering,
+    /// A restriction whose filler is itself a description: an intersection,
+    /// a union or a complement.
+    Nested,
+    /// A class given by its individuals, `{a, b, ...}`.
+    Enumerated,
+    /// Every restriction is a `SOME`.
+    ExistentialOnly,
+}
+
+/// Named class or anonymous description a restriction points at, so
+/// `SOME {A, B}` and `ONLY (A OR B)` can be compared member by member.
+#[derive(PartialEq)]
+pub enum Atom<'a> {
+    Named(&'a ClassIdentifier),
+    Anonymous(&'a Class),
+}
+
+/// Labels a class declaration with every category it falls into, in the
+/// order they are declared in `Label`.
+pub fn classify(decl: &ClassDecl) -> Vec<Label> {
+    let mut labels = Vec::new();
+
+    match decl {
+        ClassDecl::Primitive(_, properties) | ClassDecl::Defined(_, properties) => {
+            if matches!(decl, ClassDecl::Primitive(..)) {
+                labels.push(Label::Primitive);
+            } else {
+                labels.push(Label::Defined);
+            }
+
+            if is_closure(properties) {
+                labels.push(Label::Closure);
+            }
+
+            let fillers = properties
+                .iter()
+                .filter_map(|p| filler(&p.value))
+                .collect::<Vec<_>>();
+
+            if fillers.iter().any(|class| {
+                matches!(
+                    class,
+                    Class::Defined(..) | Class::Union(_) | Class::Complement(_)
+                )
+            }) {
+                labels.push(Label::Nested);
+            }
+
+            if fillers
+                .iter()
+                .any(|class| matches!(class, Class::Enumerated(..)))
+            {
+                labels.push(Label::Enumerated);
+            }
+
+            if properties.iter().all(|property| {
+                matches!(
+                    property.value.description,
+                    PropertyDescription::Object(ObjectDescription::Some(_))
+                        | PropertyDescription::Data(DataDescription::Some(_))
+                )
+            }) {
+                labels.push(Label::ExistentialOnly);
+            }
+        }
+        ClassDecl::Union(_) => labels.push(Label::Covering),
+        ClassDecl::Enumerated(_) => labels.push(Label::Enumerated),
+    }
+
+    labels
+}
+
+fn filler(property: &Property) -> Option<&Class> {
+    match &property.description {
+        PropertyDescription::Object(description) => match description {
+            ObjectDescription::Some(class)
+            | ObjectDescription::Only(class)
+            | ObjectDescription::Min(_, class)
+            | ObjectDescription::Max(_, class)
+            | ObjectDescription::Exactly(_, class) => Some(class),
+            ObjectDescription::Value(_) => None,
+        },
+        PropertyDescription::Data(_) => None,
+    }
+}
+
+pub fn atoms(class: &Class) -> Vec<Atom<'_>> {
+    match class {
+        Class::Identifier(class) => vec![Atom::Named(class)],
+        Class::Union(classes) | Class::Enumerated(classes) => {
+            classes.iter().map(Atom::Named).collect()
+        }
+        Class::Defined(..) | Class::Complement(_) => vec![Atom::Anonymous(class)],
+    }
+}
+
+fn is_closure(properties: &[Located<Property>]) -> bool {
+    properties.iter().any(|closure| {
+        let PropertyDescription::Object(ObjectDescription::Only(only)) = &closure.value.description
+        else {
+            return false;
+        };
+
+        let closed = atoms(only);
+        let mut existentials = Vec::new();
+
+        for property in properties
+            .iter()
+            .filter(|p| p.value.index == closure.value.index)
+        {
+            if let PropertyDescription::Object(ObjectDescription::Some(class)) =
+                &property.value.description
+            {
+                existentials.extend(atoms(class));
+            }
+        }
+
+        !existentials.is_empty()
+            && existentials.iter().all(|atom| closed.contains(atom))
+            && closed.iter().all(|atom| existentials.contains(atom))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        lexer,
+        parser::{self, Frame},
+        span::SourceMap,
+        table::SymbolTable,
+    };
+
+    // the labels of the only declaration in `text`
+    fn labels(text: &str) -> Vec<Label> {
+        let mut map = SourceMap::new();
+        let mut table = SymbolTable::new();
+        let file_id = map.add("test.txt".to_string(), text.to_string());
+
+        let Ok(tokens) = lexer::parse(&map, file_id, &mut table) else {
+            panic!("could not lex {:?}", text);
+        };
+        let Ok(ontology) = parser::parse(&tokens, &mut table) else {
+            panic!("could not parse {:?}", text);
+        };
+
+        match &ontology.frames[..] {
+            [frame] => match &frame.value {
+                Frame::Decl { decl, .. } => classify(&decl.value),
+                _ => panic!("{:?} is not a declaration", text),
+            },
+            _ => panic!("{:?} is not a single frame", text),
+        }
+    }
+
+    #[test]
+    fn primitive() {
+        assert_eq!(labels("Pizza hasTopping ONLY Cheese\n"), [Label::Primitive]);
+    }
+
+    #[test]
+    fn defined() {
+        assert_eq!(
+            labels("Pizza AND (hasTopping MIN 1 Cheese)\n"),
+            [Label::Defined]
+        );
+    }
+
+    #[test]
+    fn closure() {
+        assert_eq!(
+            labels("Pizza AND (hasTopping SOME Cheese) AND (hasTopping ONLY Cheese)\n"),
+            [Label::Defined, Label::Closure]
+        );
+        assert_eq!(
+            labels(
+                "Pizza AND (hasTopping SOME Cheese) AND (hasTopping SOME Ham) \
+                 AND (hasTopping ONLY (Ham OR Cheese))\n"
+            ),
+            [Label::Defined, Label::Closure, Label::Nested]
+        );
+    }
+
+    #[test]
+    fn covering() {
+        assert_eq!(labels("Pizza OR Calzone OR Focaccia\n"), [Label::Covering]);
+    }
+
+    #[test]
+    fn nested() {
+        for filler in [
+            "(Base AND (hasSize SOME Big))",
+            "(Cheese OR Ham)",
+            "(NOT Ham)",
+        ] {
+            assert_eq!(
+                labels(&format!("Pizza AND (hasTopping SOME {})\n", filler)),
+                [Label::Defined, Label::Nested, Label::ExistentialOnly]
+            );
+        }
+    }
+
+    #[test]
+    fn enumerated() {
+        assert_eq!(labels("{Thin, Thick}\n"), [Label::Enumerated]);
+        assert_eq!(
+            labels("Pizza AND (hasBase ONLY {Thin, Thick})\n"),
+            [Label::Defined, Label::Enumerated]
+        );
+    }
+
+    #[test]
+    fn existential_only() {
+        assert_eq!(
+            labels("Pizza AND (hasTopping SOME Cheese) AND (hasBase SOME Thin)\n"),
+            [Label::Defined, Label::ExistentialOnly]
+        );
+    }
+}
@@ -0,0 +1,753 @@
+//! Lossless concrete syntax tree of a file, in the green/red style.
+//!
+//! The parser does not produce this tree: it is a side index built after
+//! parsing, from the text, the tokens and the spans recorded in the
+//! abstract syntax tree, so it only has nodes where the parser kept a span.
+
+use std::{fmt::Display, ops::Range, rc::Rc};
+
+use crate::{
+    lexer::{Punctuation, Token},
+    parser::{Class, ClassDecl, Frame, ObjectDescription, Ontology, Property, PropertyDescription},
+    span::{Located, Span},
+};
+
+/// Kind of a token or a node of the concrete syntax tree.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SyntaxKind {
+    // tokens
+    Whitespace,
+    Section,
+    Iri,
+    LanguageTag,
+    Keyword,
+    Datatype,
+    OpenParen,
+    CloseParen,
+    Punctuation,
+    Cardinality,
+    ClassIdentifier,
+    PropertyIdentifier,
+    AnnotationPropertyIdentifier,
+    Literal,
+    // nodes
+    Ontology,
+    Frame,
+    Decl,
+    /// A class description that is not a whole declaration, such as a
+    /// superclass or a member of `EquivalentClasses:`.
+    Description,
+    Restriction,
+    DataRange,
+    /// `( ... )` around a restriction or a description.
+    Parenthesized,
+}
+
+/// Immutable token of the green tree, with its text and no position, so
+/// equal tokens can be shared.
+#[derive(Debug, PartialEq, Eq, Hash)]
+pub struct GreenToken {
+    kind: SyntaxKind,
+    text: String,
+}
+
+/// Immutable node of the green tree, knowing only the length of its text.
+#[derive(Debug, PartialEq, Eq, Hash)]
+pub struct GreenNode {
+    kind: SyntaxKind,
+    len: usize,
+    children: Vec<GreenElement>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum GreenElement {
+    Node(Rc<GreenNode>),
+    Token(Rc<GreenToken>),
+}
+
+impl GreenToken {
+    pub fn new(kind: SyntaxKind, text: &str) -> Self {
+        Self {
+            kind,
+            text: text.to_string(),
+        }
+    }
+}
+
+impl GreenNode {
+    pub fn new(kind: SyntaxKind, children: Vec<GreenElement>) -> Self {
+        let len = children.iter().map(GreenElement::len).sum();
+        Self {
+            kind,
+            len,
+            children,
+        }
+    }
+
+    pub fn kind(&self) -> SyntaxKind {
+        self.kind
+    }
+
+    pub fn children(&self) -> &[GreenElement] {
+        &self.children
+    }
+
+    // a copy of the node with one child replaced
+    fn with_child(&self, index: usize, child: GreenElement) -> Self {
+        let mut children = self.children.clone();
+        children[index] = child;
+        Self::new(self.kind, children)
+    }
+}
+
+impl GreenElement {
+    fn len(&self) -> usize {
+        match self {
+            Self::Node(node) => node.len,
+            Self::Token(token) => token.text.len(),
+        }
+    }
+}
+
+impl Display for GreenNode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for child in &self.children {
+            match child {
+                GreenElement::Node(node) => node.fmt(f)?,
+                GreenElement::Token(token) => f.write_str(&token.text)?,
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Node of the red tree: a green node together with its offset and parent,
+/// created on demand while walking down from the root.
+#[derive(Debug, Clone)]
+pub struct SyntaxNode(Rc<NodeData>);
+
+#[derive(Debug)]
+struct NodeData {
+    green: Rc<GreenNode>,
+    offset: usize,
+    // the parent and the index of this node among its children
+    parent: Option<(SyntaxNode, usize)>,
+}
+
+/// Token of the red tree.
+#[derive(Debug, Clone)]
+pub struct SyntaxToken {
+    green: Rc<GreenToken>,
+    offset: usize,
+    parent: SyntaxNode,
+    index: usize,
+}
+
+#[derive(Debug, Clone)]
+pub enum SyntaxElement {
+    Node(SyntaxNode),
+    Token(SyntaxToken),
+}
+
+impl SyntaxNode {
+    pub fn new_root(green: Rc<GreenNode>) -> Self {
+        Self(Rc::new(NodeData {
+            green,
+            offset: 0,
+            parent: None,
+        }))
+    }
+
+    pub fn kind(&self) -> SyntaxKind {
+        self.0.green.kind
+    }
+
+    pub fn green(&self) -> &Rc<GreenNode> {
+        &self.0.green
+    }
+
+    /// Byte range of the node in the text of the whole tree.
+    pub fn range(&self) -> Range<usize> {
+        self.0.offset..self.0.offset + self.0.green.len
+    }
+
+    pub fn text(&self) -> String {
+        self.0.green.to_string()
+    }
+
+    pub fn parent(&self) -> Option<SyntaxNode> {
+        self.0.parent.as_ref().map(|(parent, _)| parent.clone())
+    }
+
+    pub fn children_with_tokens(&self) -> Vec<SyntaxElement> {
+        let mut offset = self.0.offset;
+
+        self.0
+            .green
+            .children
+            .iter()
+            .enumerate()
+            .map(|(index, child)| {
+                let element = match child {
+                    GreenElement::Node(green) => SyntaxElement::Node(Self(Rc::new(NodeData {
+                        green: green.clone(),
+                        offset,
+                        parent: Some((self.clone(), index)),
+                    }))),
+                    GreenElement::Token(green) => SyntaxElement::Token(SyntaxToken {
+                        green: green.clone(),
+                        offset,
+                        parent: self.clone(),
+                        index,
+                    }),
+                };
+                offset += child.len();
+                element
+            })
+            .collect()
+    }
+
+    pub fn children(&self) -> Vec<SyntaxNode> {
+        self.children_with_tokens()
+            .into_iter()
+            .filter_map(|element| match element {
+                SyntaxElement::Node(node) => Some(node),
+                SyntaxElement::Token(_) => None,
+            })
+            .collect()
+    }
+
+    /// Tokens of the node and of all its descendants, trivia included, in
+    /// order.
+    pub fn tokens(&self) -> Vec<SyntaxToken> {
+        self.children_with_tokens()
+            .into_iter()
+            .flat_map(|element| match element {
+                SyntaxElement::Node(node) => node.tokens(),
+                SyntaxElement::Token(token) => vec![token],
+            })
+            .collect()
+    }
+
+    /// The node and all its descendant nodes, in preorder.
+    pub fn descendants(&self) -> Vec<SyntaxNode> {
+        let mut nodes = vec![self.clone()];
+        nodes.extend(self.children().iter().flat_map(SyntaxNode::descendants));
+        nodes
+    }
+
+    /// The green tree of the whole file with this node replaced, sharing
+    /// every node outside the path to the root.
+    pub fn replace_with(&self, green: GreenNode) -> Rc<GreenNode> {
+        let green = Rc::new(green);
+
+        match &self.0.parent {
+            Some((parent, index)) => {
+                let child = GreenElement::Node(green);
+                parent.replace_with(parent.0.green.with_child(*index, child))
+            }
+            None => green,
+        }
+    }
+}
+
+impl Display for SyntaxNode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.0.green.fmt(f)
+    }
+}
+
+impl SyntaxToken {
+    pub fn kind(&self) -> SyntaxKind {
+        self.green.kind
+    }
+
+    pub fn text(&self) -> &str {
+        &self.green.text
+    }
+
+    pub fn range(&self) -> Range<usize> {
+        self.offset..self.offset + self.green.text.len()
+    }
+
+    pub fn parent(&self) -> &SyntaxNode {
+        &self.parent
+    }
+
+    pub fn is_trivia(&self) -> bool {
+        self.kind() == SyntaxKind::Whitespace
+    }
+
+    /// The green tree of the whole file with the text of this token
+    /// replaced.
+    pub fn replace_with(&self, text: &str) -> Rc<GreenNode> {
+        let token = GreenElement::Token(Rc::new(GreenToken::new(self.kind(), text)));
+        let parent = self.parent.0.green.with_child(self.index, token);
+        self.parent.replace_with(parent)
+    }
+}
+
+/// Builds the lossless tree of a file from its text, its tokens and the
+/// spans of its parsed ontology.
+///
+/// The gaps between tokens become whitespace tokens, and every span the
+/// parser recorded becomes a node, so the text of the root is exactly the
+/// text of the file.
+pub fn build(text: &str, tokens: &[Located<Token>], ontology: &Ontology) -> SyntaxNode {
+    let mut nodes = Vec::new();
+
+    for frame in &ontology.frames {
+        nodes.push((frame.span, SyntaxKind::Frame));
+
+        match &frame.value {
+            Frame::Decl { decl, .. } => {
+                nodes.push((decl.span, SyntaxKind::Decl));
+
+                match &decl.value {
+                    ClassDecl::Defined(_, properties) | ClassDecl::Primitive(_, properties) => {
+                        push_properties(properties, &mut nodes)
+                    }
+                    ClassDecl::Enumerated(_) | ClassDecl::Union(_) => {}
+                }
+            }
+            Frame::Class { super_classes, .. } => {
+                for super_class in super_classes {
+                    nodes.push((super_class.span, SyntaxKind::Description));
+                    push_class(&super_class.value, &mut nodes);
+                }
+            }
+            Frame::DataProperty { ranges, .. } => {
+                for range in ranges {
+                    nodes.push((range.span, SyntaxKind::DataRange));
+                }
+            }
+            Frame::EquivalentClasses(misc) | Frame::DisjointClasses(misc) => {
+                for member in &misc.members {
+                    push_class(member, &mut nodes);
+                }
+            }
+            Frame::AnnotationProperty { .. }
+            | Frame::ObjectProperty { .. }
+            | Frame::DisjointProperties(_)
+            | Frame::SameIndividual(_)
+            | Frame::DifferentIndividuals(_) => {}
+        }
+    }
+
+    let ranges = nodes
+        .into_iter()
+        .flat_map(|(span, kind)| parenthesized(span.start()..span.end(), kind, tokens))
+        .collect();
+
+    SyntaxNode::new_root(Rc::new(Builder::new(text, tokens).build(ranges)))
+}
+
+fn push_properties(properties: &[Located<Property>], nodes: &mut Vec<(Span, SyntaxKind)>) {
+    for property in properties {
+        nodes.push((property.span, SyntaxKind::Restriction));
+
+        if let PropertyDescription::Object(
+            ObjectDescription::Some(class)
+            | ObjectDescription::Only(class)
+            | ObjectDescription::Min(_, class)
+            | ObjectDescription::Max(_, class)
+            | ObjectDescription::Exactly(_, class),
+        ) = &property.value.description
+        {
+            push_class(class, nodes);
+        }
+    }
+}
+
+fn push_class(class: &Class, nodes: &mut Vec<(Span, SyntaxKind)>) {
+    match class {
+        Class::Defined(_, properties) => push_properties(properties, nodes),
+        Class::Complement(class) => push_class(class, nodes),
+        Class::Identifier(_) | Class::Enumerated(_) | Class::Union(_) => {}
+    }
+}
+
+// the node of a span, inside a parenthesized node for each pair of
+// parentheses the parser merged into the span
+fn parenthesized(
+    mut range: Range<usize>,
+    kind: SyntaxKind,
+    tokens: &[Located<Token>],
+) -> Vec<(Range<usize>, SyntaxKind)> {
+    let mut nodes = Vec::new();
+
+    loop {
+        let inside = tokens
+            .iter()
+            .enumerate()
+            .filter(|(_, t)| range.start <= t.span.start() && t.span.end() <= range.end)
+            .map(|(i, _)| i)
+            .collect::<Vec<_>>();
+
+        let (Some(&first), Some(&last)) = (inside.first(), inside.last()) else {
+            break;
+        };
+
+        if !matches!(
+            tokens[first].value,
+            Token::Punctuation(Punctuation::OpenParen)
+        ) || closing(tokens, first) != Some(last)
+        {
+            break;
+        }
+
+        nodes.push((range.clone(), SyntaxKind::Parenthesized));
+        range = tokens[first + 1].span.start()..tokens[last - 1].span.end();
+    }
+
+    nodes.push((range, kind));
+    nodes
+}
+
+// index of the parenthesis closing the one at `open`
+fn closing(tokens: &[Located<Token>], open: usize) -> Option<usize> {
+    let mut depth = 0;
+
+    for (i, token) in tokens.iter().enumerate().skip(open) {
+        match token.value {
+            Token::Punctuation(Punctuation::OpenParen) => depth += 1,
+            Token::Punctuation(Punctuation::CloseParen) => {
+                depth -= 1;
+                if depth == 0 {
+                    return Some(i);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    None
+}
+
+struct Builder<'a> {
+    text: &'a str,
+    tokens: &'a [Located<Token>],
+    // nodes being built, innermost last, each with where it ends
+    stack: Vec<(SyntaxKind, usize, Vec<GreenElement>)>,
+}
+
+impl<'a> Builder<'a> {
+    fn new(text: &'a str, tokens: &'a [Located<Token>]) -> Self {
+        Self {
+            text,
+            tokens,
+            stack: vec![(SyntaxKind::Ontology, text.len(), Vec::new())],
+        }
+    }
+
+    fn build(mut self, mut ranges: Vec<(Range<usize>, SyntaxKind)>) -> GreenNode {
+        // outer nodes first, so that they are opened before the inner ones
+        ranges.sort_by_key(|(range, _)| (range.start, std::cmp::Reverse(range.end)));
+        ranges.dedup();
+        let mut ranges = ranges.into_iter().peekable();
+
+        let mut offset = 0;
+
+        for token in self.tokens {
+            let (start, end) = (token.span.start(), token.span.end());
+
+            if offset < start {
+                self.push(offset, SyntaxKind::Whitespace, &self.text[offset..start]);
+            }
+
+            self.close(start);
+
+            while let Some((range, kind)) = ranges.next_if(|(range, _)| range.start == start) {
+                self.stack.push((kind, range.end, Vec::new()));
+            }
+
+            self.push(start, kind(&token.value), &self.text[start..end]);
+            offset = end;
+        }
+
+        if offset < self.text.len() {
+            self.push(offset, SyntaxKind::Whitespace, &self.text[offset..]);
+        }
+
+        self.close(usize::MAX);
+        let (kind, _, children) = self.stack.pop().expect("the root is never closed");
+        GreenNode::new(kind, children)
+    }
+
+    fn push(&mut self, offset: usize, kind: SyntaxKind, text: &str) {
+        self.close(offset);
+        let token = GreenElement::Token(Rc::new(GreenToken::new(kind, text)));
+        self.stack.last_mut().unwrap().2.push(token);
+    }
+
+    // closes the nodes that end at or before `offset`, but not the root
+    fn close(&mut self, offset: usize) {
+        while self.stack.len() > 1 && self.stack.last().unwrap().1 <= offset {
+            let (kind, _, children) = self.stack.pop().unwrap();
+            let node = GreenElement::Node(Rc::new(GreenNode::new(kind, children)));
+            self.stack.last_mut().unwrap().2.push(node);
+        }
+    }
+}
+
+/// Typed view of the nodes of one kind.
+pub trait AstNode: Sized {
+    fn cast(node: SyntaxNode) -> Option<Self>;
+    fn syntax(&self) -> &SyntaxNode;
+}
+
+/// The root of a file.
+pub struct OntologySyntax(SyntaxNode);
+
+/// A frame, of any section or a declaration.
+pub struct FrameSyntax(SyntaxNode);
+
+/// A class declaration, with the annotations before it left in its frame.
+pub struct DeclSyntax(SyntaxNode);
+
+/// A property restriction, without the parentheses around it.
+pub struct RestrictionSyntax(SyntaxNode);
+
+/// Parentheses around a restriction or a description.
+pub struct ParenthesizedSyntax(SyntaxNode);
+
+impl AstNode for OntologySyntax {
+    fn cast(node: SyntaxNode) -> Option<Self> {
+        (node.kind() == SyntaxKind::Ontology).then_some(Self(node))
+    }
+
+    fn syntax(&self) -> &SyntaxNode {
+        &self.0
+    }
+}
+
+impl AstNode for FrameSyntax {
+    fn cast(node: SyntaxNode) -> Option<Self> {
+        (node.kind() == SyntaxKind::Frame).then_some(Self(node))
+    }
+
+    fn syntax(&self) -> &SyntaxNode {
+        &self.0
+    }
+}
+
+impl AstNode for DeclSyntax {
+    fn cast(node: SyntaxNode) -> Option<Self> {
+        (node.kind() == SyntaxKind::Decl).then_some(Self(node))
+    }
+
+    fn syntax(&self) -> &SyntaxNode {
+        &self.0
+    }
+}
+
+impl AstNode for RestrictionSyntax {
+    fn cast(node: SyntaxNode) -> Option<Self> {
+        (node.kind() == SyntaxKind::Restriction).then_some(Self(node))
+    }
+
+    fn syntax(&self) -> &SyntaxNode {
+        &self.0
+    }
+}
+
+impl AstNode for ParenthesizedSyntax {
+    fn cast(node: SyntaxNode) -> Option<Self> {
+        (node.kind() == SyntaxKind::Parenthesized).then_some(Self(node))
+    }
+
+    fn syntax(&self) -> &SyntaxNode {
+        &self.0
+    }
+}
+
+impl OntologySyntax {
+    pub fn frames(&self) -> Vec<FrameSyntax> {
+        self.0
+            .children()
+            .into_iter()
+            .filter_map(FrameSyntax::cast)
+            .collect()
+    }
+}
+
+impl FrameSyntax {
+    /// The parsed frame this node was built from.
+    pub fn frame<'o>(&self, ontology: &'o Ontology) -> Option<&'o Located<Frame>> {
+        let range = self.0.range();
+        ontology
+            .frames
+            .iter()
+            .find(|frame| (frame.span.start()..frame.span.end()) == range)
+    }
+
+    /// The section opening the frame, absent for declarations.
+    pub fn section(&self) -> Option<SyntaxToken> {
+        first_token(&self.0, SyntaxKind::Section)
+    }
+
+    pub fn decl(&self) -> Option<DeclSyntax> {
+        self.0.children().into_iter().find_map(DeclSyntax::cast)
+    }
+
+    /// Every restriction in the frame, nested ones included.
+    pub fn restrictions(&self) -> Vec<RestrictionSyntax> {
+        self.0
+            .descendants()
+            .into_iter()
+            .filter_map(RestrictionSyntax::cast)
+            .collect()
+    }
+}
+
+impl DeclSyntax {
+    /// The class the declaration is about.
+    pub fn subject(&self) -> Option<SyntaxToken> {
+        first_token(&self.0, SyntaxKind::ClassIdentifier)
+    }
+
+    /// The restrictions of the declaration, parenthesized or not.
+    pub fn restrictions(&self) -> Vec<RestrictionSyntax> {
+        self.0
+            .children()
+            .into_iter()
+            .filter_map(|node| match ParenthesizedSyntax::cast(node.clone()) {
+                Some(parenthesized) => parenthesized.innermost(),
+                None => Some(node),
+            })
+            .filter_map(RestrictionSyntax::cast)
+            .collect()
+    }
+}
+
+impl RestrictionSyntax {
+    pub fn property(&self) -> Option<SyntaxToken> {
+        first_token(&self.0, SyntaxKind::PropertyIdentifier)
+    }
+
+    /// `SOME`, `ONLY`, `VALUE`, `MIN`, `MAX` or `EXACTLY`.
+    pub fn keyword(&self) -> Option<SyntaxToken> {
+        first_token(&self.0, SyntaxKind::Keyword)
+    }
+}
+
+impl ParenthesizedSyntax {
+    pub fn inner(&self) -> Option<SyntaxNode> {
+        self.0.children().into_iter().next()
+    }
+
+    /// The node inside all the parentheses nested directly in these.
+    pub fn innermost(&self) -> Option<SyntaxNode> {
+        let inner = self.inner()?;
+
+        match ParenthesizedSyntax::cast(inner.clone()) {
+            Some(parenthesized) => parenthesized.innermost(),
+            None => Some(inner),
+        }
+    }
+}
+
+// the first token of a kind among the direct children of a node
+fn first_token(node: &SyntaxNode, kind: SyntaxKind) -> Option<SyntaxToken> {
+    node.children_with_tokens()
+        .into_iter()
+        .find_map(|element| match element {
+            SyntaxElement::Token(token) if token.kind() == kind => Some(token),
+            _ => None,
+        })
+}
+
+fn kind(token: &Token) -> SyntaxKind {
+    match token {
+        Token::Section(_) => SyntaxKind::Section,
+        Token::Iri(_) => SyntaxKind::Iri,
+        Token::LanguageTag(_) => SyntaxKind::LanguageTag,
+        Token::Keyword(_) => SyntaxKind::Keyword,
+        Token::Datatype(_) => SyntaxKind::Datatype,
+        Token::Punctuation(Punctuation::OpenParen) => SyntaxKind::OpenParen,
+        Token::Punctuation(Punctuation::CloseParen) => SyntaxKind::CloseParen,
+        Token::Punctuation(_) => SyntaxKind::Punctuation,
+        Token::Cardinality(_) => SyntaxKind::Cardinality,
+        Token::ClassIdentifier { .. } => SyntaxKind::ClassIdentifier,
+        Token::PropertyIdentifier { .. } => SyntaxKind::PropertyIdentifier,
+        Token::AnnotationPropertyIdentifier { .. } => SyntaxKind::AnnotationPropertyIdentifier,
+        Token::Literal { .. } => SyntaxKind::Literal,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{lexer, parser, span::SourceMap, table::SymbolTable};
+
+    fn tree(text: &str) -> SyntaxNode {
+        let mut map = SourceMap::new();
+        let mut table = SymbolTable::new();
+        let file_id = map.add("test.txt".to_string(), text.to_string());
+
+        let Ok(tokens) = lexer::parse(&map, file_id, &mut table) else {
+            panic!("could not lex {:?}", text);
+        };
+        let Ok(ontology) = parser::parse(&tokens, &mut table) else {
+            panic!("could not parse {:?}", text);
+        };
+        build(text, &tokens, &ontology)
+    }
+
+    #[test]
+    fn round_trips_the_text_of_each_file() {
+        for text in [
+            include_str!("../file1.txt"),
+            include_str!("../file2.txt"),
+            include_str!("../file3.txt"),
+        ] {
+            assert_eq!(tree(text).text(), text);
+        }
+    }
+
+    #[test]
+    fn round_trips_irregular_spacing_and_parentheses() {
+        let text = "  Pizza   AND ((hasTopping SOME  (Cheese)))\n\n\nClass:\tCheese\n    SubClassOf:  Food \n";
+        let root = tree(text);
+
+        assert_eq!(root.text(), text);
+        assert!(root
+            .descendants()
+            .iter()
+            .any(|node| node.kind() == SyntaxKind::Parenthesized));
+    }
+
+    #[test]
+    fn replaces_a_token_and_keeps_everything_else() {
+        let text = "Pizza AND (hasTopping SOME Cheese)\n";
+        let root = tree(text);
+
+        let token = root
+            .tokens()
+            .into_iter()
+            .find(|token| token.text() == "Cheese")
+            .unwrap();
+        let renamed = SyntaxNode::new_root(token.replace_with("Mozzarella"));
+
+        assert_eq!(renamed.text(), "Pizza AND (hasTopping SOME Mozzarella)\n");
+        assert_eq!(renamed.range(), 0..text.len() + 4);
+        assert_eq!(tree(&renamed.text()).text(), renamed.text());
+    }
+
+    #[test]
+    fn shares_the_nodes_outside_the_replaced_path() {
+        let root = tree("Pizza AND (hasTopping SOME Cheese)\nClass: Cheese SubClassOf: Food\n");
+
+        let token = root
+            .tokens()
+            .into_iter()
+            .find(|token| token.text() == "Food")
+            .unwrap();
+        let renamed = SyntaxNode::new_root(token.replace_with("Dairy"));
+
+        let (before, after) = (root.children(), renamed.children());
+        assert!(Rc::ptr_eq(before[0].green(), after[0].green()));
+        assert!(!Rc::ptr_eq(before[1].green(), after[1].green()));
+    }
+}
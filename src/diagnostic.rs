@@ -0,0 +1,52 @@
+use crate::span::{SourceMap, Span};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Severity {
+    Warning,
+}
+
+/// A problem found by a semantic check, which does not stop the analysis.
+pub struct Diagnostic {
+    pub severity: Severity,
+    pub span: Span,
+    pub message: String,
+    /// Other places involved in the problem, each with a short note.
+    pub related: Vec<(Span, String)>,
+}
+
+impl Diagnostic {
+    pub fn warning(span: Span, message: String) -> Self {
+        Self {
+            severity: Severity::Warning,
+            span,
+            message,
+            related: Vec::new(),
+        }
+    }
+
+    pub fn with_related(mut self, span: Span, note: &str) -> Self {
+        self.related.push((span, note.to_string()));
+        self
+    }
+
+    pub fn describe(&self, map: &SourceMap) -> String {
+        let mut message = format!(
+            "{:?} at {}: {} at '{}'",
+            self.severity,
+            map.location(self.span),
+            self.message,
+            map.fragment(self.span)
+        );
+
+        for (span, note) in &self.related {
+            message += &format!(
+                "\n    {}: {}: '{}'",
+                map.location(*span),
+                note,
+                map.fragment(*span)
+            );
+        }
+
+        message
+    }
+}
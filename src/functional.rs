@@ -0,0 +1,619 @@
+use std::collections::{BTreeSet, HashMap};
+
+use crate::{
+    lexer::Datatype,
+    parser::{
+        Annotation, AnnotationValue, Class, ClassDecl, Data, DataDescription, Frame,
+        ObjectDescription, Ontology, Property, PropertyDescription, PropertyIdentifier,
+    },
+    span::Located,
+    table::{PropertyType, SymbolTable, Type},
+};
+
+/// Prefixes with a well-known namespace. The first four are always declared,
+/// the others only when an annotation property uses them.
+pub const PREFIXES: [(&str, &str); 7] = [
+    ("owl", "http://www.w3.org/2002/07/owl#"),
+    ("rdf", "http://www.w3.org/1999/02/22-rdf-syntax-ns#"),
+    ("rdfs", "http://www.w3.org/2000/01/rdf-schema#"),
+    ("xsd", "http://www.w3.org/2001/XMLSchema#"),
+    ("dc", "http://purl.org/dc/elements/1.1/"),
+    ("dcterms", "http://purl.org/dc/terms/"),
+    ("skos", "http://www.w3.org/2004/02/skos/core#"),
+];
+
+/// The namespace of the entities of an ontology when none is given: its IRI
+/// followed by `#`, or `http://example.org/ontology#` if it has none.
+pub fn namespace(ontology: &Ontology) -> String {
+    match &ontology.iri {
+        Some(iri) if iri.value.ends_with(['#', '/']) => iri.value.clone(),
+        Some(iri) => format!("{}#", iri.value),
+        None => "http://example.org/ontology#".to_string(),
+    }
+}
+
+/// The namespace of a prefix of annotation properties: the well-known one,
+/// or `<namespace><prefix>/` for the others.
+pub fn prefix(prefix: &str, namespace: &str) -> String {
+    PREFIXES
+        .iter()
+        .find(|(name, _)| *name == prefix)
+        .map_or_else(
+            || format!("{}{}/", namespace, prefix),
+            |(_, iri)| iri.to_string(),
+        )
+}
+
+/// The datatype of the values of each data property, by its index: its
+/// first `Range:`, or else the first datatype a restriction gives it.
+/// Literals of `VALUE` restrictions are typed with it.
+pub fn datatypes(ontology: &Ontology) -> HashMap<usize, Datatype> {
+    let mut datatypes = HashMap::new();
+
+    for frame in &ontology.frames {
+        if let Frame::DataProperty {
+            property, ranges, ..
+        } = &frame.value
+        {
+            if let Some(range) = ranges.first() {
+                datatypes
+                    .entry(property.index)
+                    .or_insert(range.value.datatype);
+            }
+        }
+    }
+
+    fn visit(class: &Class, datatypes: &mut HashMap<usize, Datatype>) {
+        match class {
+            Class::Defined(_, properties) => {
+                for property in properties {
+                    restriction(&property.value, datatypes);
+                }
+            }
+            Class::Complement(class) => visit(class, datatypes),
+            Class::Identifier(_) | Class::Enumerated(_) | Class::Union(_) => {}
+        }
+    }
+
+    fn restriction(property: &Property, datatypes: &mut HashMap<usize, Datatype>) {
+        match &property.description {
+            PropertyDescription::Object(description) => match description {
+                ObjectDescription::Some(class)
+                | ObjectDescription::Only(class)
+                | ObjectDescription::Min(_, class)
+                | ObjectDescription::Max(_, class)
+                | ObjectDescription::Exactly(_, class) => visit(class, datatypes),
+                ObjectDescription::Value(_) => {}
+            },
+            PropertyDescription::Data(description) => match description {
+                DataDescription::Some(data)
+                | DataDescription::Only(data)
+                | DataDescription::Min(_, data)
+                | DataDescription::Max(_, data)
+                | DataDescription::Exactly(_, data) => {
+                    datatypes.entry(property.index).or_insert(data.datatype);
+                }
+                DataDescription::Value(_) => {}
+            },
+        }
+    }
+
+    for frame in &ontology.frames {
+        match &frame.value {
+            Frame::Decl { decl, .. } => match &decl.value {
+                ClassDecl::Defined(_, properties) | ClassDecl::Primitive(_, properties) => {
+                    for property in properties {
+                        restriction(&property.value, &mut datatypes);
+                    }
+                }
+                ClassDecl::Enumerated(_) | ClassDecl::Union(_) => {}
+            },
+            Frame::Class { super_classes, .. } => {
+                for super_class in super_classes {
+                    visit(&super_class.value, &mut datatypes);
+                }
+            }
+            Frame::EquivalentClasses(misc) | Frame::DisjointClasses(misc) => {
+                for member in &misc.members {
+                    visit(member, &mut datatypes);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    datatypes
+}
+
+/// The datatype of the literal of a `VALUE` restriction on `property`: the
+/// one `datatypes` gives the property, or a string if it has none anywhere.
+pub fn value_datatype(datatypes: &HashMap<usize, Datatype>, property: usize) -> Datatype {
+    datatypes
+        .get(&property)
+        .copied()
+        .unwrap_or(Datatype::String)
+}
+
+/// Whether the members of a `DisjointProperties:` frame are data
+/// properties: they are if any of them is known to be one, and object
+/// properties otherwise.
+pub fn disjoint_data_properties(members: &[PropertyIdentifier], table: &SymbolTable) -> bool {
+    members.iter().any(|member| {
+        matches!(
+            table.get(member.index).map(|s| s.type_()),
+            Some(Type::Property(Some(PropertyType::Data)))
+        )
+    })
+}
+
+/// The prefix and local name of an annotation property, which is named by
+/// a prefixed name such as `rdfs:label` rather than in the namespace of the
+/// ontology.
+pub fn annotation_prefix(id: &str) -> Option<(&str, &str)> {
+    id.split_once(':')
+}
+
+/// A literal in Functional-Style and Turtle syntax, typed with `datatype`.
+pub fn literal(lexical: &str, datatype: Datatype) -> String {
+    format!("{}^^xsd:{}", quote(lexical), datatype.name())
+}
+
+/// A string in double quotes, with its quotes and backslashes escaped.
+pub fn quote(text: &str) -> String {
+    format!("\"{}\"", text.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+// kinds of entities, in the order they are declared
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum Entity {
+    Class,
+    ObjectProperty,
+    DataProperty,
+    AnnotationProperty,
+    NamedIndividual,
+}
+
+struct Exporter<'a> {
+    table: &'a SymbolTable,
+    datatypes: HashMap<usize, Datatype>,
+    entities: BTreeSet<(Entity, String)>,
+    prefixes: BTreeSet<String>,
+    axioms: Vec<String>,
+    declarations: usize,
+}
+
+/// Exports an ontology to OWL 2 Functional-Style syntax, with its classes,
+/// properties and individuals in `namespace`, the default prefix.
+///
+/// A class declaration such as `Pizza AND (hasTopping SOME CheeseTopping)`
+/// describes an anonymous class, which is exported as a class of its own,
+/// `:declaration<n>` for the n-th declaration, equivalent to the
+/// description if it is defined and a subclass of it if it is primitive.
+pub fn ontology(ontology: &Ontology, table: &SymbolTable, namespace: &str) -> String {
+    let mut exporter = Exporter {
+        table,
+        datatypes: datatypes(ontology),
+        entities: BTreeSet::new(),
+        prefixes: BTreeSet::new(),
+        axioms: Vec::new(),
+        declarations: 0,
+    };
+
+    for frame in &ontology.frames {
+        exporter.frame(&frame.value);
+    }
+
+    let mut out = format!("Prefix(:=<{}>)\n", namespace);
+
+    for (name, iri) in &PREFIXES[..4] {
+        out += &format!("Prefix({}:=<{}>)\n", name, iri);
+    }
+
+    for name in &exporter.prefixes {
+        if !PREFIXES[..4].iter().any(|(prefix, _)| prefix == name) {
+            out += &format!("Prefix({}:=<{}>)\n", name, prefix(name, namespace));
+        }
+    }
+
+    out.push('\n');
+
+    match &ontology.iri {
+        Some(iri) => out += &format!("Ontology(<{}>\n", iri.value),
+        None => out += "Ontology(\n",
+    }
+
+    for import in &ontology.imports {
+        out += &format!("Import(<{}>)\n", import.value);
+    }
+
+    if !exporter.entities.is_empty() {
+        out.push('\n');
+    }
+
+    for (entity, iri) in &exporter.entities {
+        out += &format!("Declaration({:?}({}))\n", entity, iri);
+    }
+
+    if !exporter.axioms.is_empty() {
+        out.push('\n');
+    }
+
+    for axiom in &exporter.axioms {
+        out += &format!("{}\n", axiom);
+    }
+
+    out += ")\n";
+    out
+}
+
+impl<'a> Exporter<'a> {
+    fn id(&self, index: usize) -> &'a str {
+        self.table.get(index).map_or("?", |s| s.id())
+    }
+
+    // the IRI of a symbol, declared as the given kind of entity
+    fn entity(&mut self, entity: Entity, index: usize) -> String {
+        let iri = format!(":{}", self.id(index));
+        self.entities.insert((entity, iri.clone()));
+        iri
+    }
+
+    fn class_iri(&mut self, index: usize) -> String {
+        self.entity(Entity::Class, index)
+    }
+
+    fn individual(&mut self, index: usize) -> String {
+        self.entity(Entity::NamedIndividual, index)
+    }
+
+    fn object_property(&mut self, index: usize) -> String {
+        self.entity(Entity::ObjectProperty, index)
+    }
+
+    fn data_property(&mut self, index: usize) -> String {
+        self.entity(Entity::DataProperty, index)
+    }
+
+    fn annotation_property(&mut self, index: usize) -> String {
+        let iri = self.id(index).to_string();
+
+        if let Some((prefix, _)) = annotation_prefix(&iri) {
+            self.prefixes.insert(prefix.to_string());
+        }
+
+        self.entities
+            .insert((Entity::AnnotationProperty, iri.clone()));
+        iri
+    }
+
+    fn frame(&mut self, frame: &Frame) {
+        match frame {
+            Frame::Class {
+                class,
+                annotations,
+                super_classes,
+            } => {
+                let iri = self.class_iri(class.index);
+                self.assertions(&iri, annotations);
+
+                for super_class in super_classes {
+                    let super_class = self.class(&super_class.value);
+                    self.axioms
+                        .push(format!("SubClassOf({} {})", iri, super_class));
+                }
+            }
+            Frame::AnnotationProperty {
+                property,
+                annotations,
+            } => {
+                let iri = self.annotation_property(property.index);
+                self.assertions(&iri, annotations);
+            }
+            Frame::Decl { annotations, decl } => {
+                self.declarations += 1;
+                let iri = format!(":declaration{}", self.declarations);
+                self.entities.insert((Entity::Class, iri.clone()));
+                self.assertions(&iri, annotations);
+
+                let axiom = match &decl.value {
+                    ClassDecl::Defined(class, properties) => {
+                        let description = self.intersection(class.index, properties);
+                        format!("EquivalentClasses({} {})", iri, description)
+                    }
+                    ClassDecl::Primitive(class, properties) => {
+                        let description = self.intersection(class.index, properties);
+                        format!("SubClassOf({} {})", iri, description)
+                    }
+                    ClassDecl::Enumerated(individuals) => {
+                        let description = self.one_of(individuals.iter().map(|i| i.index));
+                        format!("EquivalentClasses({} {})", iri, description)
+                    }
+                    ClassDecl::Union(classes) => {
+                        let description = self.union(classes.iter().map(|c| c.index));
+                        format!("EquivalentClasses({} {})", iri, description)
+                    }
+                };
+
+                self.axioms.push(axiom);
+            }
+            Frame::ObjectProperty {
+                property,
+                annotations,
+                chains,
+            } => {
+                let iri = self.object_property(property.index);
+                self.assertions(&iri, annotations);
+
+                for chain in chains {
+                    let properties = chain
+                        .0
+                        .iter()
+                        .map(|property| self.object_property(property.index))
+                        .collect::<Vec<_>>();
+                    self.axioms.push(format!(
+                        "SubObjectPropertyOf(ObjectPropertyChain({}) {})",
+                        properties.join(" "),
+                        iri
+                    ));
+                }
+            }
+            Frame::DataProperty {
+                property,
+                annotations,
+                ranges,
+            } => {
+                let iri = self.data_property(property.index);
+                self.assertions(&iri, annotations);
+
+                for range in ranges {
+                    let range = self.data(&range.value);
+                    self.axioms
+                        .push(format!("DataPropertyRange({} {})", iri, range));
+                }
+            }
+            Frame::DisjointClasses(misc) | Frame::EquivalentClasses(misc) => {
+                let name = match frame {
+                    Frame::DisjointClasses(_) => "DisjointClasses",
+                    _ => "EquivalentClasses",
+                };
+                let members = misc
+                    .members
+                    .iter()
+                    .map(|member| self.class(member))
+                    .collect();
+                self.misc(name, &misc.annotations, members);
+            }
+            Frame::DisjointProperties(misc) => {
+                let (name, members) = if disjoint_data_properties(&misc.members, self.table) {
+                    let members = misc
+                        .members
+                        .iter()
+                        .map(|member| self.data_property(member.index))
+                        .collect();
+                    ("DisjointDataProperties", members)
+                } else {
+                    let members = misc
+                        .members
+                        .iter()
+                        .map(|member| self.object_property(member.index))
+                        .collect();
+                    ("DisjointObjectProperties", members)
+                };
+
+                self.misc(name, &misc.annotations, members);
+            }
+            Frame::SameIndividual(misc) | Frame::DifferentIndividuals(misc) => {
+                let name = match frame {
+                    Frame::SameIndividual(_) => "SameIndividual",
+                    _ => "DifferentIndividuals",
+                };
+                let members = misc
+                    .members
+                    .iter()
+                    .map(|member| self.individual(member.index))
+                    .collect();
+                self.misc(name, &misc.annotations, members);
+            }
+        }
+    }
+
+    // an axiom over the members of a misc frame, annotated with its
+    // annotations
+    fn misc(&mut self, name: &str, annotations: &[Annotation], members: Vec<String>) {
+        let annotations = annotations
+            .iter()
+            .map(|annotation| {
+                let property = self.annotation_property(annotation.property.index);
+                format!(
+                    "Annotation({} {}) ",
+                    property,
+                    self.value(&annotation.value)
+                )
+            })
+            .collect::<String>();
+
+        self.axioms
+            .push(format!("{}({}{})", name, annotations, members.join(" ")));
+    }
+
+    fn assertions(&mut self, subject: &str, annotations: &[Annotation]) {
+        for annotation in annotations {
+            let property = self.annotation_property(annotation.property.index);
+            let value = self.value(&annotation.value);
+            self.axioms.push(format!(
+                "AnnotationAssertion({} {} {})",
+                property, subject, value
+            ));
+        }
+    }
+
+    fn value(&self, value: &AnnotationValue) -> String {
+        match value {
+            AnnotationValue::Literal(literal, Some(language)) => {
+                format!("{}@{}", quote(self.id(literal.index)), language)
+            }
+            AnnotationValue::Literal(literal, None) => {
+                self::literal(self.id(literal.index), Datatype::String)
+            }
+            AnnotationValue::Iri(iri) => format!("<{}>", iri),
+        }
+    }
+
+    fn class(&mut self, class: &Class) -> String {
+        match class {
+            Class::Identifier(class) => self.class_iri(class.index),
+            Class::Defined(class, properties) => self.intersection(class.index, properties),
+            Class::Enumerated(individuals) => self.one_of(individuals.iter().map(|i| i.index)),
+            Class::Union(classes) => self.union(classes.iter().map(|c| c.index)),
+            Class::Complement(class) => format!("ObjectComplementOf({})", self.class(class)),
+        }
+    }
+
+    fn intersection(&mut self, class: usize, properties: &[Located<Property>]) -> String {
+        let mut operands = vec![self.class_iri(class)];
+
+        for property in properties {
+            operands.push(self.restriction(&property.value));
+        }
+
+        if operands.len() == 1 {
+            operands.remove(0)
+        } else {
+            format!("ObjectIntersectionOf({})", operands.join(" "))
+        }
+    }
+
+    fn union(&mut self, classes: impl Iterator<Item = usize>) -> String {
+        let classes = classes.map(|c| self.class_iri(c)).collect::<Vec<_>>();
+        format!("ObjectUnionOf({})", classes.join(" "))
+    }
+
+    fn one_of(&mut self, individuals: impl Iterator<Item = usize>) -> String {
+        let individuals = individuals.map(|i| self.individual(i)).collect::<Vec<_>>();
+        format!("ObjectOneOf({})", individuals.join(" "))
+    }
+
+    fn restriction(&mut self, property: &Property) -> String {
+        match &property.description {
+            PropertyDescription::Object(description) => {
+                let p = self.object_property(property.index);
+
+                match description {
+                    ObjectDescription::Some(class) => {
+                        format!("ObjectSomeValuesFrom({} {})", p, self.class(class))
+                    }
+                    ObjectDescription::Only(class) => {
+                        format!("ObjectAllValuesFrom({} {})", p, self.class(class))
+                    }
+                    ObjectDescription::Value(individual) => {
+                        format!(
+                            "ObjectHasValue({} {})",
+                            p,
+                            self.individual(individual.index)
+                        )
+                    }
+                    ObjectDescription::Min(n, class) => {
+                        format!("ObjectMinCardinality({} {} {})", n, p, self.class(class))
+                    }
+                    ObjectDescription::Max(n, class) => {
+                        format!("ObjectMaxCardinality({} {} {})", n, p, self.class(class))
+                    }
+                    ObjectDescription::Exactly(n, class) => {
+                        format!("ObjectExactCardinality({} {} {})", n, p, self.class(class))
+                    }
+                }
+            }
+            PropertyDescription::Data(description) => {
+                let p = self.data_property(property.index);
+
+                match description {
+                    DataDescription::Some(data) => {
+                        format!("DataSomeValuesFrom({} {})", p, self.data(data))
+                    }
+                    DataDescription::Only(data) => {
+                        format!("DataAllValuesFrom({} {})", p, self.data(data))
+                    }
+                    DataDescription::Value(literal) => {
+                        let literal = self::literal(
+                            self.id(literal.value.index),
+                            value_datatype(&self.datatypes, property.index),
+                        );
+                        format!("DataHasValue({} {})", p, literal)
+                    }
+                    DataDescription::Min(n, data) => {
+                        format!("DataMinCardinality({} {} {})", n, p, self.data(data))
+                    }
+                    DataDescription::Max(n, data) => {
+                        format!("DataMaxCardinality({} {} {})", n, p, self.data(data))
+                    }
+                    DataDescription::Exactly(n, data) => {
+                        format!("DataExactCardinality({} {} {})", n, p, self.data(data))
+                    }
+                }
+            }
+        }
+    }
+
+    fn data(&self, data: &Data) -> String {
+        match &data.restriction {
+            Some(restriction) => format!(
+                "DatatypeRestriction(xsd:{} xsd:{} {})",
+                data.datatype.name(),
+                restriction.0.facet(),
+                literal(self.id(restriction.1.index), data.datatype)
+            ),
+            None => format!("xsd:{}", data.datatype.name()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{lexer, parser, span::SourceMap};
+
+    fn export(text: &str) -> String {
+        let mut map = SourceMap::new();
+        let mut table = SymbolTable::new();
+        let file_id = map.add("test.txt".to_string(), text.to_string());
+
+        let Ok(tokens) = lexer::parse(&map, file_id, &mut table) else {
+            panic!("could not lex {:?}", text);
+        };
+        let Ok(parsed) = parser::parse(&tokens, &mut table) else {
+            panic!("could not parse {:?}", text);
+        };
+        ontology(&parsed, &table, &namespace(&parsed))
+    }
+
+    #[test]
+    fn exports_a_small_ontology() {
+        let text = "Class: Margherita\n    SubClassOf: (Pizza AND (hasTopping SOME Mozzarella))\n\
+                    Pizza AND (hasCalories VALUE \"800\")\n\
+                    DataProperty: hasCalories\n    Range: integer[>=\"0\"]\n";
+
+        assert_eq!(
+            export(text),
+            r#"Prefix(:=<http://example.org/ontology#>)
+Prefix(owl:=<http://www.w3.org/2002/07/owl#>)
+Prefix(rdf:=<http://www.w3.org/1999/02/22-rdf-syntax-ns#>)
+Prefix(rdfs:=<http://www.w3.org/2000/01/rdf-schema#>)
+Prefix(xsd:=<http://www.w3.org/2001/XMLSchema#>)
+
+Ontology(
+
+Declaration(Class(:Margherita))
+Declaration(Class(:Mozzarella))
+Declaration(Class(:Pizza))
+Declaration(Class(:declaration1))
+Declaration(ObjectProperty(:hasTopping))
+Declaration(DataProperty(:hasCalories))
+
+SubClassOf(:Margherita ObjectIntersectionOf(:Pizza ObjectSomeValuesFrom(:hasTopping :Mozzarella)))
+EquivalentClasses(:declaration1 ObjectIntersectionOf(:Pizza DataHasValue(:hasCalories "800"^^xsd:integer)))
+DataPropertyRange(:hasCalories DatatypeRestriction(xsd:integer xsd:minInclusive "0"^^xsd:integer))
+)
+"#
+        );
+    }
+}
@@ -0,0 +1,493 @@
+use std::collections::{hash_map::Entry, HashMap, VecDeque};
+
+use crate::{
+    parser::{Class, ClassDecl, Frame, ObjectDescription, Ontology, Property, PropertyDescription},
+    span::{Located, SourceMap, Span},
+    table::SymbolTable,
+};
+
+/// A class of the told hierarchy.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Node {
+    /// A named class, by its index in the symbol table.
+    Named(usize),
+    /// The anonymous class described by a declaration such as
+    /// `Pizza AND (hasTopping SOME CheeseTopping)`, by the span of the
+    /// declaration.
+    Anonymous(Span),
+}
+
+/// Subclass relation given explicitly by the declarations of an ontology,
+/// without any inference. A class may have several parents, so this is a
+/// DAG rather than a tree, unless the ontology has cycles.
+#[derive(Default)]
+pub struct Hierarchy {
+    nodes: Vec<Node>,
+    positions: HashMap<Node, usize>,
+    // (child, parent, span of the axiom relating them)
+    edges: Vec<(usize, usize, Span)>,
+    // named classes stated equivalent to each other, which may form cycles
+    equivalences: Vec<(usize, usize)>,
+    // named classes used in a restriction filler of their own description,
+    // with the (property, span) restrictions leading to them
+    self_references: Vec<(usize, Vec<(usize, Span)>)>,
+}
+
+impl Hierarchy {
+    pub fn new<'o>(ontologies: impl IntoIterator<Item = &'o Ontology>) -> Self {
+        let mut hierarchy = Self::default();
+
+        for ontology in ontologies {
+            for frame in &ontology.frames {
+                match &frame.value {
+                    Frame::Decl { decl, .. } => match &decl.value {
+                        // the subject of a declaration is the superclass of
+                        // the class being described
+                        ClassDecl::Defined(super_class, properties)
+                        | ClassDecl::Primitive(super_class, properties) => {
+                            hierarchy
+                                .find_restriction_self_references(super_class.index, properties);
+                            hierarchy.add(
+                                Node::Anonymous(decl.span),
+                                Node::Named(super_class.index),
+                                decl.span,
+                            );
+                        }
+                        ClassDecl::Enumerated(_) | ClassDecl::Union(_) => {}
+                    },
+                    Frame::Class {
+                        class,
+                        super_classes,
+                        ..
+                    } => {
+                        for super_class in super_classes {
+                            hierarchy.find_self_references(class.index, &super_class.value);
+
+                            let parent = match &super_class.value {
+                                Class::Identifier(parent) | Class::Defined(parent, _) => parent,
+                                Class::Enumerated(_) | Class::Union(_) | Class::Complement(_) => {
+                                    continue
+                                }
+                            };
+
+                            hierarchy.add(
+                                Node::Named(class.index),
+                                Node::Named(parent.index),
+                                super_class.span,
+                            );
+                        }
+                    }
+                    Frame::EquivalentClasses(misc) => {
+                        let named = misc.members.iter().filter_map(|member| match member {
+                            Class::Identifier(class) => Some(class.index),
+                            _ => None,
+                        });
+
+                        for class in named.clone() {
+                            for member in &misc.members {
+                                hierarchy.find_self_references(class, member);
+                            }
+
+                            for other in named.clone().filter(|other| *other != class) {
+                                let class = hierarchy.position(Node::Named(class));
+                                let other = hierarchy.position(Node::Named(other));
+                                hierarchy.equivalences.push((class, other));
+                            }
+
+                            // a class equivalent to an intersection is below
+                            // each of its operands
+                            for member in &misc.members {
+                                if let Class::Defined(parent, _) = member {
+                                    hierarchy.add(
+                                        Node::Named(class),
+                                        Node::Named(parent.index),
+                                        frame.span,
+                                    );
+                                }
+                            }
+                        }
+                    }
+                    _ => {}
+                }
+            }
+        }
+
+        hierarchy
+    }
+
+    fn find_self_references(&mut self, class: usize, description: &Class) {
+        let mut paths = Vec::new();
+        self_references(class, description, &mut Vec::new(), &mut paths);
+        self.self_references
+            .extend(paths.into_iter().map(|path| (class, path)));
+    }
+
+    fn find_restriction_self_references(&mut self, class: usize, properties: &[Located<Property>]) {
+        let mut paths = Vec::new();
+        restriction_self_references(class, properties, &mut Vec::new(), &mut paths);
+        self.self_references
+            .extend(paths.into_iter().map(|path| (class, path)));
+    }
+
+    fn position(&mut self, node: Node) -> usize {
+        *self.positions.entry(node).or_insert_with(|| {
+            self.nodes.push(node);
+            self.nodes.len() - 1
+        })
+    }
+
+    fn add(&mut self, child: Node, parent: Node, span: Span) {
+        let child = self.position(child);
+        let parent = self.position(parent);
+
+        if !self
+            .edges
+            .iter()
+            .any(|(c, p, _)| (*c, *p) == (child, parent))
+        {
+            self.edges.push((child, parent, span));
+        }
+    }
+
+    /// Direct superclasses of `node`.
+    pub fn parents(&self, node: Node) -> Vec<Node> {
+        let Some(&position) = self.positions.get(&node) else {
+            return Vec::new();
+        };
+
+        self.edges
+            .iter()
+            .filter(|(child, _, _)| *child == position)
+            .map(|(_, parent, _)| self.nodes[*parent])
+            .collect()
+    }
+
+    /// Direct subclasses of `node`.
+    pub fn children(&self, node: Node) -> Vec<Node> {
+        let Some(&position) = self.positions.get(&node) else {
+            return Vec::new();
+        };
+
+        self.edges
+            .iter()
+            .filter(|(_, parent, _)| *parent == position)
+            .map(|(child, _, _)| self.nodes[*child])
+            .collect()
+    }
+
+    /// Every superclass of `node`, direct or not, nearest first.
+    pub fn ancestors(&self, node: Node) -> Vec<Node> {
+        let mut ancestors = self.parents(node);
+        let mut i = 0;
+
+        while let Some(&ancestor) = ancestors.get(i) {
+            for parent in self.parents(ancestor) {
+                if !ancestors.contains(&parent) {
+                    ancestors.push(parent);
+                }
+            }
+            i += 1;
+        }
+
+        ancestors
+    }
+
+    /// The tops of the hierarchy: classes without superclasses, or whose
+    /// superclasses are all in a cycle with them.
+    pub fn roots(&self) -> Vec<Node> {
+        self.nodes
+            .iter()
+            .copied()
+            .filter(|node| {
+                self.ancestors(*node)
+                    .into_iter()
+                    .all(|ancestor| ancestor == *node || self.ancestors(ancestor).contains(node))
+            })
+            .collect()
+    }
+
+    /// Named classes used inside a restriction filler of their own
+    /// description, such as `X` in `Class: X SubClassOf: Y AND (hasPart SOME
+    /// (X AND ...))`, each with the `(property, span)` restrictions nested on
+    /// the way to it, outermost first.
+    pub fn self_references(&self) -> &[(usize, Vec<(usize, Span)>)] {
+        &self.self_references
+    }
+
+    /// Cycles of subclass axioms, each as the chain of `(child, parent,
+    /// span)` edges that closes it, leaving out classes that are all stated
+    /// equivalent to each other.
+    pub fn cycles(&self) -> Vec<Vec<(Node, Node, Span)>> {
+        let mut cycles = Vec::new();
+
+        for component in self.components() {
+            let looped = component.len() > 1
+                || self
+                    .edges
+                    .iter()
+                    .any(|(child, parent, _)| *child == component[0] && *parent == component[0]);
+
+            if !looped || (component.len() > 1 && self.all_equivalent(&component)) {
+                continue;
+            }
+
+            if let Some(cycle) = self.cycle_through(component[0], &component) {
+                let cycle = cycle
+                    .into_iter()
+                    .map(|(child, parent, span)| (self.nodes[child], self.nodes[parent], span))
+                    .collect();
+                cycles.push(cycle);
+            }
+        }
+
+        cycles
+    }
+
+    // strongly connected components of the subclass edges, by Tarjan's
+    // algorithm, in order of their first node
+    fn components(&self) -> Vec<Vec<usize>> {
+        struct State {
+            index: Vec<Option<usize>>,
+            low: Vec<usize>,
+            stack: Vec<usize>,
+            on_stack: Vec<bool>,
+            next: usize,
+            components: Vec<Vec<usize>>,
+        }
+
+        fn visit(hierarchy: &Hierarchy, node: usize, state: &mut State) {
+            state.index[node] = Some(state.next);
+            state.low[node] = state.next;
+            state.next += 1;
+            state.stack.push(node);
+            state.on_stack[node] = true;
+
+            for &(child, parent, _) in &hierarchy.edges {
+                if child != node {
+                    continue;
+                }
+
+                match state.index[parent] {
+                    None => {
+                        visit(hierarchy, parent, state);
+                        state.low[node] = state.low[node].min(state.low[parent]);
+                    }
+                    Some(index) if state.on_stack[parent] => {
+                        state.low[node] = state.low[node].min(index);
+                    }
+                    Some(_) => {}
+                }
+            }
+
+            if Some(state.low[node]) == state.index[node] {
+                let mut component = Vec::new();
+
+                while let Some(top) = state.stack.pop() {
+                    state.on_stack[top] = false;
+                    component.push(top);
+
+                    if top == node {
+                        break;
+                    }
+                }
+
+                component.sort();
+                state.components.push(component);
+            }
+        }
+
+        let mut state = State {
+            index: vec![None; self.nodes.len()],
+            low: vec![0; self.nodes.len()],
+            stack: Vec::new(),
+            on_stack: vec![false; self.nodes.len()],
+            next: 0,
+            components: Vec::new(),
+        };
+
+        for node in 0..self.nodes.len() {
+            if state.index[node].is_none() {
+                visit(self, node, &mut state);
+            }
+        }
+
+        state.components.sort();
+        state.components
+    }
+
+    fn all_equivalent(&self, component: &[usize]) -> bool {
+        // classes stated equivalent, directly or through others
+        let mut equivalent = vec![component[0]];
+        let mut i = 0;
+
+        while let Some(&class) = equivalent.get(i) {
+            for &(a, b) in &self.equivalences {
+                if a == class && !equivalent.contains(&b) {
+                    equivalent.push(b);
+                }
+            }
+            i += 1;
+        }
+
+        component.iter().all(|node| equivalent.contains(node))
+    }
+
+    // shortest chain of edges from `start` back to itself, inside the
+    // given strongly connected component
+    fn cycle_through(
+        &self,
+        start: usize,
+        component: &[usize],
+    ) -> Option<Vec<(usize, usize, Span)>> {
+        let mut reached: HashMap<usize, (usize, usize, Span)> = HashMap::new();
+        let mut queue = VecDeque::from([start]);
+
+        while let Some(node) = queue.pop_front() {
+            for &(child, parent, span) in &self.edges {
+                if child != node || !component.contains(&parent) {
+                    continue;
+                }
+
+                if parent == start {
+                    let mut cycle = vec![(child, parent, span)];
+                    let mut current = child;
+
+                    while current != start {
+                        let edge = reached[&current];
+                        cycle.push(edge);
+                        current = edge.0;
+                    }
+
+                    cycle.reverse();
+                    return Some(cycle);
+                }
+
+                if let Entry::Vacant(entry) = reached.entry(parent) {
+                    entry.insert((child, parent, span));
+                    queue.push_back(parent);
+                }
+            }
+        }
+
+        None
+    }
+
+    /// The hierarchy as an indented tree, a class listed once under each of
+    /// its parents.
+    pub fn render(&self, map: &SourceMap, table: &SymbolTable) -> String {
+        let mut tree = String::new();
+
+        for root in self.roots() {
+            self.render_node(root, 0, map, table, &mut tree);
+        }
+
+        tree
+    }
+
+    fn render_node(
+        &self,
+        node: Node,
+        depth: usize,
+        map: &SourceMap,
+        table: &SymbolTable,
+        tree: &mut String,
+    ) {
+        tree.push_str(&format!(
+            "{:1$}{2}\n",
+            "",
+            depth * 2,
+            name(node, map, table)
+        ));
+
+        let ancestors = self.ancestors(node);
+
+        for child in self.children(node) {
+            // a child that is also an ancestor closes a cycle
+            if child != node && !ancestors.contains(&child) {
+                self.render_node(child, depth + 1, map, table, tree);
+            }
+        }
+    }
+}
+
+// collects the paths of restrictions under which `class` occurs in
+// `description`; occurrences outside any restriction are subclass edges
+fn self_references(
+    class: usize,
+    description: &Class,
+    path: &mut Vec<(usize, Span)>,
+    paths: &mut Vec<Vec<(usize, Span)>>,
+) {
+    let nested = !path.is_empty();
+
+    let properties = match description {
+        Class::Identifier(other) => {
+            if nested && other.index == class {
+                paths.push(path.clone());
+            }
+            return;
+        }
+        Class::Defined(other, properties) => {
+            if nested && other.index == class {
+                paths.push(path.clone());
+                return;
+            }
+            properties
+        }
+        Class::Union(classes) => {
+            if nested && classes.iter().any(|other| other.index == class) {
+                paths.push(path.clone());
+            }
+            return;
+        }
+        Class::Enumerated(_) => return,
+        Class::Complement(inner) => return self_references(class, inner, path, paths),
+    };
+
+    restriction_self_references(class, properties, path, paths);
+}
+
+// the same, for the restrictions of an intersection
+fn restriction_self_references(
+    class: usize,
+    properties: &[Located<Property>],
+    path: &mut Vec<(usize, Span)>,
+    paths: &mut Vec<Vec<(usize, Span)>>,
+) {
+    for property in properties {
+        let PropertyDescription::Object(description) = &property.value.description else {
+            continue;
+        };
+
+        let filler = match description {
+            ObjectDescription::Some(filler)
+            | ObjectDescription::Only(filler)
+            | ObjectDescription::Min(_, filler)
+            | ObjectDescription::Max(_, filler)
+            | ObjectDescription::Exactly(_, filler) => filler,
+            ObjectDescription::Value(_) => continue,
+        };
+
+        path.push((property.value.index, property.span));
+        self_references(class, filler, path, paths);
+        path.pop();
+    }
+}
+
+/// How a class is shown to the user: its name, or the location and first
+/// line of the declaration describing it.
+pub fn name(node: Node, map: &SourceMap, table: &SymbolTable) -> String {
+    match node {
+        Node::Named(index) => table.get(index).map_or("?", |s| s.id()).to_string(),
+        Node::Anonymous(span) => {
+            let fragment = map.fragment(span);
+            let line = fragment.lines().next().unwrap_or("");
+            let ellipsis = if line.len() < fragment.len() {
+                " ..."
+            } else {
+                ""
+            };
+            format!("{}: {}{}", map.location(span), line, ellipsis)
+        }
+    }
+}
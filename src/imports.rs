@@ -0,0 +1,215 @@
+use std::{
+    collections::{HashMap, HashSet},
+    fs::{canonicalize, read_to_string},
+    io,
+    path::{Path, PathBuf},
+};
+
+use crate::{
+    lexer::{self, LexerErr, Token},
+    parser::{self, Ontology, ParserErr},
+    span::{Located, SourceMap},
+    table::SymbolTable,
+};
+
+/// Maps ontology IRIs to local files, so `Import:` declarations can be
+/// resolved without network access.
+#[derive(Debug, Default)]
+pub struct Catalog {
+    entries: HashMap<String, PathBuf>,
+}
+
+impl Catalog {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Reads a catalog where every non-empty line is `<iri> path`. Lines
+    /// starting with `#` are comments, and relative paths are resolved
+    /// against the directory of the catalog itself.
+    pub fn load(path: &Path) -> io::Result<Self> {
+        let content = read_to_string(path)?;
+        let base = path.parent().unwrap_or(Path::new(""));
+        let mut entries = HashMap::new();
+
+        for (number, line) in content.lines().enumerate() {
+            let line = line.trim();
+
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let Some((iri, target)) = line.split_once(char::is_whitespace) else {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    format!("{}:{}: expected '<iri> path'", path.display(), number + 1),
+                ));
+            };
+
+            let iri = iri.trim_start_matches('<').trim_end_matches('>');
+            entries.insert(iri.to_string(), base.join(target.trim()));
+        }
+
+        Ok(Self { entries })
+    }
+
+    /// Finds the file for an imported IRI: catalog entries take precedence,
+    /// then `file:` IRIs and plain paths relative to the importing file.
+    pub fn resolve(&self, iri: &str, importer: &Path) -> Option<PathBuf> {
+        if let Some(path) = self.entries.get(iri) {
+            return Some(path.clone());
+        }
+
+        let path = match iri.strip_prefix("file://") {
+            Some(path) => path,
+            None if iri.contains("://") => return None,
+            None => iri,
+        };
+
+        let base = importer.parent().unwrap_or(Path::new(""));
+
+        Some(base.join(path))
+    }
+}
+
+pub struct Loaded {
+    pub tokens: Vec<Located<Token>>,
+    pub ontology: Ontology,
+}
+
+pub enum LoadErr {
+    Read {
+        filename: String,
+        err: io::Error,
+        import: Option<Located<String>>,
+    },
+    Lexer(Located<LexerErr>),
+    Parser(ParserErr),
+    Unresolved(Located<String>),
+    Cycle(Vec<Located<String>>),
+}
+
+/// Loads files together with the closure of their imports into a single
+/// symbol table, in depth-first order.
+pub struct Loader<'c> {
+    catalog: &'c Catalog,
+    loaded: HashSet<PathBuf>,
+    // files currently being loaded, each with the import that led to it
+    stack: Vec<(PathBuf, Option<Located<String>>)>,
+    pub files: Vec<Loaded>,
+}
+
+impl<'c> Loader<'c> {
+    pub fn new(catalog: &'c Catalog) -> Self {
+        Self {
+            catalog,
+            loaded: HashSet::new(),
+            stack: Vec::new(),
+            files: Vec::new(),
+        }
+    }
+
+    pub fn load(
+        &mut self,
+        map: &mut SourceMap,
+        table: &mut SymbolTable,
+        filename: &str,
+    ) -> Result<(), LoadErr> {
+        self.load_file(map, table, filename, None)
+    }
+
+    fn load_file(
+        &mut self,
+        map: &mut SourceMap,
+        table: &mut SymbolTable,
+        filename: &str,
+        import: Option<Located<String>>,
+    ) -> Result<(), LoadErr> {
+        let read_err = |err| LoadErr::Read {
+            filename: filename.to_string(),
+            err,
+            import: import.clone(),
+        };
+
+        let path = canonicalize(filename).map_err(read_err)?;
+
+        if let Some(position) = self.stack.iter().position(|(p, _)| *p == path) {
+            let mut cycle = self.stack[position + 1..]
+                .iter()
+                .filter_map(|(_, import)| import.clone())
+                .collect::<Vec<_>>();
+            cycle.extend(import);
+            return Err(LoadErr::Cycle(cycle));
+        }
+
+        if !self.loaded.insert(path.clone()) {
+            return Ok(());
+        }
+
+        let content = read_to_string(&path).map_err(read_err)?;
+        let file_id = map.add(filename.to_string(), content);
+
+        let tokens = lexer::parse(map, file_id, table).map_err(LoadErr::Lexer)?;
+        let ontology = parser::parse(&tokens, table).map_err(LoadErr::Parser)?;
+
+        self.stack.push((path, import));
+
+        for import in &ontology.imports {
+            let Some(target) = self.catalog.resolve(&import.value, Path::new(filename)) else {
+                return Err(LoadErr::Unresolved(import.clone()));
+            };
+
+            let target = target.to_string_lossy().to_string();
+            self.load_file(map, table, &target, Some(import.clone()))?;
+        }
+
+        self.stack.pop();
+
+        self.files.push(Loaded { tokens, ontology });
+
+        Ok(())
+    }
+}
+
+impl LoadErr {
+    pub fn describe(&self, map: &SourceMap) -> String {
+        match self {
+            Self::Read {
+                filename,
+                err,
+                import,
+            } => {
+                let mut message =
+                    format!("{}: {}\nCould not read file {}", filename, err, filename);
+
+                if let Some(import) = import {
+                    message += &format!(" imported at {}", map.location(import.span));
+                }
+
+                message
+            }
+            Self::Lexer(err) => {
+                format!("Lexer error at {}: {:?}", map.location(err.span), err.value)
+            }
+            Self::Parser(err) => format!("Parser error at {}", err.describe(map)),
+            Self::Unresolved(import) => format!(
+                "Import error at {}: could not resolve <{}>",
+                map.location(import.span),
+                import.value
+            ),
+            Self::Cycle(imports) => {
+                let mut message = "Import error: cycle in imports".to_string();
+
+                for import in imports {
+                    message += &format!(
+                        "\n    {}: Import: <{}>",
+                        map.location(import.span),
+                        import.value
+                    );
+                }
+
+                message
+            }
+        }
+    }
+}
@@ -0,0 +1,213 @@
+use crate::{
+    lexer::Datatype,
+    parser::{Data, Literal, Restriction, RestrictionType},
+    table::SymbolTable,
+};
+
+/// Bound of an interval, `(value, inclusive)`.
+type Bound = (f64, bool);
+
+/// Values allowed by a numeric data range such as `integer[>"10"]`.
+#[derive(Debug, Clone, Copy)]
+pub struct Interval {
+    /// The datatype of the values, `None` once datatypes with no value in
+    /// common, such as `float` and `decimal`, were intersected.
+    datatype: Option<Datatype>,
+    lower: Option<Bound>,
+    upper: Option<Bound>,
+}
+
+impl Interval {
+    /// The values of a numeric data range, or `None` for strings and
+    /// literals that are not numbers.
+    pub fn of(data: &Data, table: &SymbolTable) -> Option<Self> {
+        let mut interval = Self {
+            datatype: match data.datatype {
+                Datatype::String => return None,
+                datatype => Some(datatype),
+            },
+            lower: None,
+            upper: None,
+        };
+
+        if let Some(Restriction(type_, literal)) = &data.restriction {
+            let value = number(literal, table)?;
+
+            match type_ {
+                RestrictionType::GreaterThanEqual => interval.lower = Some((value, true)),
+                RestrictionType::GreaterThan => interval.lower = Some((value, false)),
+                RestrictionType::LessThanEqual => interval.upper = Some((value, true)),
+                RestrictionType::LessThan => interval.upper = Some((value, false)),
+            }
+        }
+
+        Some(interval)
+    }
+
+    pub fn intersect(&self, other: &Self) -> Self {
+        // the tighter bound wins, and an exclusive bound is tighter than an
+        // inclusive one on the same value
+        let lower = match (self.lower, other.lower) {
+            (Some(a), Some(b)) if a.0 > b.0 || (a.0 == b.0 && !a.1) => Some(a),
+            (Some(_), Some(b)) => Some(b),
+            (a, b) => a.or(b),
+        };
+
+        let upper = match (self.upper, other.upper) {
+            (Some(a), Some(b)) if a.0 < b.0 || (a.0 == b.0 && !a.1) => Some(a),
+            (Some(_), Some(b)) => Some(b),
+            (a, b) => a.or(b),
+        };
+
+        // integers are decimals, but floats are neither
+        let datatype = match (self.datatype, other.datatype) {
+            (Some(a), Some(b)) if a == b => Some(a),
+            (Some(Datatype::Integer), Some(Datatype::Decimal))
+            | (Some(Datatype::Decimal), Some(Datatype::Integer)) => Some(Datatype::Integer),
+            _ => None,
+        };
+
+        Self {
+            datatype,
+            lower,
+            upper,
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        let Some(datatype) = self.datatype else {
+            return true;
+        };
+
+        let (Some((lower, lower_inclusive)), Some((upper, upper_inclusive))) =
+            (self.lower, self.upper)
+        else {
+            return false;
+        };
+
+        if datatype == Datatype::Integer {
+            // the smallest and largest integers inside the bounds
+            let first = if lower_inclusive {
+                lower.ceil()
+            } else {
+                lower.floor() + 1.0
+            };
+            let last = if upper_inclusive {
+                upper.floor()
+            } else {
+                upper.ceil() - 1.0
+            };
+            first > last
+        } else {
+            lower > upper || (lower == upper && !(lower_inclusive && upper_inclusive))
+        }
+    }
+
+    pub fn contains(&self, value: f64) -> bool {
+        let above = match self.lower {
+            Some((lower, true)) => value >= lower,
+            Some((lower, false)) => value > lower,
+            None => true,
+        };
+
+        let below = match self.upper {
+            Some((upper, true)) => value <= upper,
+            Some((upper, false)) => value < upper,
+            None => true,
+        };
+
+        let datatype = match self.datatype {
+            Some(Datatype::Integer) => value.fract() == 0.0,
+            Some(_) => true,
+            None => false,
+        };
+
+        above && below && datatype
+    }
+}
+
+/// The numeric value of a literal, if it has one.
+pub fn number(literal: &Literal, table: &SymbolTable) -> Option<f64> {
+    let lexical = table.get(literal.index)?.id();
+    lexical.parse::<f64>().ok().filter(|value| !value.is_nan())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn interval(integer: bool, lower: Option<Bound>, upper: Option<Bound>) -> Interval {
+        let datatype = if integer {
+            Datatype::Integer
+        } else {
+            Datatype::Decimal
+        };
+
+        Interval {
+            datatype: Some(datatype),
+            lower,
+            upper,
+        }
+    }
+
+    #[test]
+    fn integer_bounds_without_an_integer_between_are_empty() {
+        // integer[>"2"] and integer[<"3"]
+        assert!(interval(true, Some((2.0, false)), Some((3.0, false))).is_empty());
+        // integer[>="2.1"] and integer[<="2.9"]
+        assert!(interval(true, Some((2.1, true)), Some((2.9, true))).is_empty());
+        // integer[>"2"] and integer[<"2.5"]
+        assert!(interval(true, Some((2.0, false)), Some((2.5, false))).is_empty());
+    }
+
+    #[test]
+    fn integer_bounds_around_an_integer_are_not_empty() {
+        assert!(!interval(true, Some((2.0, false)), Some((3.0, true))).is_empty());
+        assert!(!interval(true, Some((2.5, true)), Some((3.5, false))).is_empty());
+        assert!(!interval(true, Some((3.0, true)), Some((3.0, true))).is_empty());
+        assert!(!interval(true, Some((2.0, false)), None).is_empty());
+    }
+
+    #[test]
+    fn decimal_bounds_are_empty_only_when_they_cross() {
+        assert!(!interval(false, Some((2.0, false)), Some((3.0, false))).is_empty());
+        assert!(interval(false, Some((3.0, true)), Some((3.0, false))).is_empty());
+        assert!(interval(false, Some((3.0, true)), Some((2.0, true))).is_empty());
+    }
+
+    #[test]
+    fn intersection_with_an_integer_range_is_integer() {
+        // decimal[>"2"] and integer[<"3"]
+        let decimal = interval(false, Some((2.0, false)), None);
+        let integer = interval(true, None, Some((3.0, false)));
+
+        assert!(decimal.intersect(&integer).is_empty());
+        assert!(!decimal.is_empty() && !integer.is_empty());
+    }
+
+    #[test]
+    fn disjoint_datatypes_have_no_value_in_common() {
+        let float = Interval {
+            datatype: Some(Datatype::Float),
+            lower: None,
+            upper: None,
+        };
+        let decimal = interval(false, None, None);
+        let integer = interval(true, Some((2.0, true)), None);
+
+        assert!(float.intersect(&decimal).is_empty());
+        assert!(integer.intersect(&float).is_empty());
+        assert!(!integer.intersect(&float).contains(3.0));
+        assert!(!float.intersect(&float).is_empty());
+    }
+
+    #[test]
+    fn integers_are_decimals() {
+        let integer = interval(true, Some((2.0, true)), None);
+        let decimal = interval(false, None, Some((2.5, true)));
+
+        assert!(!integer.intersect(&decimal).is_empty());
+        assert!(decimal.intersect(&integer).contains(2.0));
+        assert!(!decimal.intersect(&integer).contains(2.25));
+    }
+}
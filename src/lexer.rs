@@ -1,8 +1,9 @@
 use crate::{
-    span::{Located, Source, Span},
+    span::{FileId, Located, SourceMap, Span},
     table::{SymbolTable, Type},
 };
 
+#[allow(clippy::upper_case_acronyms)]
 #[derive(Debug, Clone, Copy)]
 pub enum Keyword {
     SOME,
@@ -16,9 +17,11 @@ pub enum Keyword {
     NOT,
     AND,
     OR,
+    /// `o`, the property chain operator; only recognized in lowercase.
+    O,
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub enum Datatype {
     Integer,
     Decimal,
@@ -26,6 +29,49 @@ pub enum Datatype {
     String,
 }
 
+impl Datatype {
+    /// The name of the datatype as written in Manchester syntax.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Datatype::Integer => "integer",
+            Datatype::Decimal => "decimal",
+            Datatype::Float => "float",
+            Datatype::String => "string",
+        }
+    }
+
+    /// Whether `lexical`, the contents of a literal without its quotes, is
+    /// a valid value of this datatype.
+    pub fn accepts(&self, lexical: &str) -> bool {
+        let unsigned = lexical.strip_prefix(['+', '-']).unwrap_or(lexical);
+        let digits = |s: &str| !s.is_empty() && s.chars().all(|c| c.is_ascii_digit());
+        let decimal = |s: &str| match s.split_once('.') {
+            Some((int, frac)) => {
+                (digits(int) || int.is_empty())
+                    && (digits(frac) || frac.is_empty())
+                    && !(int.is_empty() && frac.is_empty())
+            }
+            None => digits(s),
+        };
+
+        match self {
+            Datatype::Integer => digits(unsigned),
+            Datatype::Decimal => decimal(unsigned),
+            Datatype::Float => {
+                matches!(lexical, "INF" | "+INF" | "-INF" | "NaN")
+                    || match unsigned.split_once(['e', 'E']) {
+                        Some((mantissa, exponent)) => {
+                            let exponent = exponent.strip_prefix(['+', '-']).unwrap_or(exponent);
+                            decimal(mantissa) && digits(exponent)
+                        }
+                        None => decimal(unsigned),
+                    }
+            }
+            Datatype::String => true,
+        }
+    }
+}
+
 #[derive(Debug, Clone, Copy)]
 pub enum Punctuation {
     OpenParen,
@@ -41,7 +87,32 @@ pub enum Punctuation {
     GreaterThan,
 }
 
+/// Manchester syntax keywords that open a frame or a section, always
+/// written with a trailing colon.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Section {
+    Ontology,
+    Import,
+    Class,
+    AnnotationProperty,
+    Annotations,
+    DisjointClasses,
+    EquivalentClasses,
+    DisjointProperties,
+    SameIndividual,
+    DifferentIndividuals,
+    ObjectProperty,
+    SubPropertyChain,
+    DataProperty,
+    Range,
+    SubClassOf,
+}
+
 pub enum TokenType {
+    Section(Section),
+    Iri(String),
+    AnnotationPropertyIdentifier(String),
+    LanguageTag(String),
     Keyword(Keyword),
     Datatype(Datatype),
     Punctuation(Punctuation),
@@ -56,25 +127,55 @@ pub enum LexerErr {
     UnrecognizedToken,
 }
 
+/// Remaining input of the lexer, keeping the content at hand so fragments
+/// can be inspected without going through the `SourceMap`.
+#[derive(Clone, Copy)]
+struct Input<'s> {
+    content: &'s str,
+    span: Span,
+}
+
+impl<'s> Input<'s> {
+    fn fragment(&self) -> &'s str {
+        &self.content[self.span.start()..self.span.end()]
+    }
+
+    fn shift(&self, n: usize) -> Self {
+        Self {
+            span: self.span.shift(n),
+            ..*self
+        }
+    }
+
+    fn split(&self, n: usize) -> (Self, Self) {
+        let (remaining, taken) = self.span.split(n);
+        (self.with_span(remaining), self.with_span(taken))
+    }
+
+    fn with_span(&self, span: Span) -> Self {
+        Self { span, ..*self }
+    }
+}
+
 pub struct LexerResult<'s, T> {
-    remaining: Span<'s>,
+    remaining: Input<'s>,
     result: Result<T, LexerErr>,
 }
 
 impl<'s, T> LexerResult<'s, T> {
-    fn new(remaining: Span<'s>, result: Result<T, LexerErr>) -> Self {
+    fn new(remaining: Input<'s>, result: Result<T, LexerErr>) -> Self {
         Self { remaining, result }
     }
 
-    fn ok(remaining: Span<'s>, value: T) -> Self {
+    fn ok(remaining: Input<'s>, value: T) -> Self {
         Self::new(remaining, Ok(value))
     }
 
-    fn err(remaining: Span<'s>, err: LexerErr) -> Self {
+    fn err(remaining: Input<'s>, err: LexerErr) -> Self {
         Self::new(remaining, Err(err))
     }
 
-    fn result(self) -> Result<(Span<'s>, T), (Span<'s>, LexerErr)> {
+    fn result(self) -> Result<(Input<'s>, T), (Input<'s>, LexerErr)> {
         match self.result {
             Ok(value) => Ok((self.remaining, value)),
             Err(err) => Err((self.remaining, err)),
@@ -88,14 +189,17 @@ impl<'s, T> LexerResult<'s, T> {
         }
     }
 
-    fn or_else<F: FnOnce(Span<'s>) -> LexerResult<'s, T>>(self, f: F) -> LexerResult<'s, T> {
+    fn or_else<F: FnOnce(Input<'s>) -> LexerResult<'s, T>>(self, f: F) -> LexerResult<'s, T> {
         match self.result {
             Ok(value) => LexerResult::ok(self.remaining, value),
             Err(_) => f(self.remaining),
         }
     }
 
-    fn and_then<U, F: FnOnce(Span<'s>, T) -> LexerResult<'s, U>>(self, f: F) -> LexerResult<'s, U> {
+    fn and_then<U, F: FnOnce(Input<'s>, T) -> LexerResult<'s, U>>(
+        self,
+        f: F,
+    ) -> LexerResult<'s, U> {
         match self.result {
             Ok(value) => f(self.remaining, value),
             Err(err) => LexerResult::err(self.remaining, err),
@@ -103,27 +207,28 @@ impl<'s, T> LexerResult<'s, T> {
     }
 }
 
-fn skip_whitespace<'s>(src: Span<'s>) -> Span<'s> {
+fn skip_whitespace<'s>(src: Input<'s>) -> Input<'s> {
     let whitespace_len = src
         .fragment()
         .chars()
         .take_while(|c| c.is_whitespace())
-        .count();
+        .map(char::len_utf8)
+        .sum();
 
     src.shift(whitespace_len)
 }
 
-fn parse_char<'s>(src: Span<'s>, c: char) -> LexerResult<'s, Located<'s, char>> {
+fn parse_char<'s>(src: Input<'s>, c: char) -> LexerResult<'s, Located<char>> {
     match src.fragment().chars().next().filter(|&c_| c_ == c) {
         None => LexerResult::err(src, LexerErr::UnrecognizedToken),
         Some(matched) => {
-            let (remaining, span) = src.split(1);
-            LexerResult::ok(remaining, Located::new(matched, span))
+            let (remaining, span) = src.split(matched.len_utf8());
+            LexerResult::ok(remaining, Located::new(matched, span.span))
         }
     }
 }
 
-fn parse_seq<'s>(src: Span<'s>, sequence: &str) -> LexerResult<'s, Located<'s, &'s str>> {
+fn parse_seq<'s>(src: Input<'s>, sequence: &str) -> LexerResult<'s, Located<&'s str>> {
     match src
         .fragment()
         .get(0..sequence.len())
@@ -132,52 +237,56 @@ fn parse_seq<'s>(src: Span<'s>, sequence: &str) -> LexerResult<'s, Located<'s, &
         None => LexerResult::err(src, LexerErr::UnrecognizedToken),
         Some(matched) => {
             let (remaining, span) = src.split(matched.len());
-            LexerResult::ok(remaining, Located::new(matched, span))
+            LexerResult::ok(remaining, Located::new(matched, span.span))
         }
     }
 }
 
-fn parse_seq_any_casing<'s>(
-    src: Span<'s>,
-    sequence: &str,
-) -> LexerResult<'s, Located<'s, &'s str>> {
-    match src.fragment().get(0..sequence.len()).filter(|substr| {
-        substr.to_string() == sequence.to_lowercase()
-            || substr.to_string() == sequence.to_uppercase()
-    }) {
+fn parse_seq_any_casing<'s>(src: Input<'s>, sequence: &str) -> LexerResult<'s, Located<&'s str>> {
+    match src
+        .fragment()
+        .get(0..sequence.len())
+        .filter(|substr| sequence.to_lowercase() == *substr || sequence.to_uppercase() == *substr)
+    {
         None => LexerResult::err(src, LexerErr::UnrecognizedToken),
         Some(matched) => {
             let (remaining, span) = src.split(matched.len());
-            LexerResult::ok(remaining, Located::new(matched, span))
+            LexerResult::ok(remaining, Located::new(matched, span.span))
         }
     }
 }
 
 fn parse_if<'s, F: FnMut(char) -> bool>(
-    src: Span<'s>,
+    src: Input<'s>,
     mut predicate: F,
-) -> LexerResult<'s, Located<'s, char>> {
+) -> LexerResult<'s, Located<char>> {
     match src.fragment().chars().next().filter(|c| predicate(*c)) {
         None => LexerResult::err(src, LexerErr::UnrecognizedToken),
         Some(matched) => {
-            let (remaining, span) = src.split(1);
-            LexerResult::ok(remaining, Located::new(matched, span))
+            let (remaining, span) = src.split(matched.len_utf8());
+            LexerResult::ok(remaining, Located::new(matched, span.span))
         }
     }
 }
 
 fn parse_while<'s, F: FnMut(char) -> bool>(
-    src: Span<'s>,
+    src: Input<'s>,
     mut predicate: F,
-) -> (Span<'s>, Located<'s, &'s str>) {
-    let len = src.fragment().chars().take_while(|c| predicate(*c)).count();
+) -> (Input<'s>, Located<&'s str>) {
+    // spans are measured in bytes, so sum the encoded length of each char
+    let len = src
+        .fragment()
+        .chars()
+        .take_while(|c| predicate(*c))
+        .map(char::len_utf8)
+        .sum();
 
     let (remaining, located) = src.split(len);
 
-    (remaining, Located::new(located.fragment(), located))
+    (remaining, Located::new(located.fragment(), located.span))
 }
 
-fn parse_keyword<'s>(src: Span<'s>) -> LexerResult<'s, Located<'s, Keyword>> {
+fn parse_keyword<'s>(src: Input<'s>) -> LexerResult<'s, Located<Keyword>> {
     LexerResult::err(src, LexerErr::UnrecognizedToken)
         .or_else(|src| parse_seq_any_casing(src, "SOME").map(|k| k.map(|_| Keyword::SOME)))
         .or_else(|src| parse_seq_any_casing(src, "ALL").map(|k| k.map(|_| Keyword::ALL)))
@@ -190,6 +299,7 @@ fn parse_keyword<'s>(src: Span<'s>) -> LexerResult<'s, Located<'s, Keyword>> {
         .or_else(|src| parse_seq_any_casing(src, "NOT").map(|k| k.map(|_| Keyword::NOT)))
         .or_else(|src| parse_seq_any_casing(src, "AND").map(|k| k.map(|_| Keyword::AND)))
         .or_else(|src| parse_seq_any_casing(src, "OR").map(|k| k.map(|_| Keyword::OR)))
+        .or_else(|src| parse_seq(src, "o").map(|k| k.map(|_| Keyword::O)))
         .and_then(|remaining, k| {
             parse_if(remaining, |c| !c.is_alphabetic() && c != '_')
                 .and_then(|_, _| LexerResult::ok(remaining, k))
@@ -197,7 +307,7 @@ fn parse_keyword<'s>(src: Span<'s>) -> LexerResult<'s, Located<'s, Keyword>> {
         })
 }
 
-fn parse_datatype<'s>(src: Span<'s>) -> LexerResult<'s, Located<'s, Datatype>> {
+fn parse_datatype<'s>(src: Input<'s>) -> LexerResult<'s, Located<Datatype>> {
     LexerResult::err(src, LexerErr::UnrecognizedToken)
         .or_else(|src| parse_seq_any_casing(src, "INTEGER").map(|l| l.map(|_| Datatype::Integer)))
         .or_else(|src| parse_seq_any_casing(src, "DECIMAL").map(|l| l.map(|_| Datatype::Decimal)))
@@ -210,7 +320,72 @@ fn parse_datatype<'s>(src: Span<'s>) -> LexerResult<'s, Located<'s, Datatype>> {
         })
 }
 
-fn parse_punctuation<'s>(src: Span<'s>) -> LexerResult<'s, Located<'s, Punctuation>> {
+fn parse_section<'s>(src: Input<'s>) -> LexerResult<'s, Located<Section>> {
+    LexerResult::err(src, LexerErr::UnrecognizedToken)
+        .or_else(|src| parse_seq(src, "Ontology:").map(|s| s.map(|_| Section::Ontology)))
+        .or_else(|src| parse_seq(src, "Import:").map(|s| s.map(|_| Section::Import)))
+        .or_else(|src| parse_seq(src, "Class:").map(|s| s.map(|_| Section::Class)))
+        .or_else(|src| {
+            parse_seq(src, "AnnotationProperty:").map(|s| s.map(|_| Section::AnnotationProperty))
+        })
+        .or_else(|src| parse_seq(src, "Annotations:").map(|s| s.map(|_| Section::Annotations)))
+        .or_else(|src| {
+            parse_seq(src, "DisjointClasses:").map(|s| s.map(|_| Section::DisjointClasses))
+        })
+        .or_else(|src| {
+            parse_seq(src, "EquivalentClasses:").map(|s| s.map(|_| Section::EquivalentClasses))
+        })
+        .or_else(|src| {
+            parse_seq(src, "DisjointProperties:").map(|s| s.map(|_| Section::DisjointProperties))
+        })
+        .or_else(|src| {
+            parse_seq(src, "SameIndividual:").map(|s| s.map(|_| Section::SameIndividual))
+        })
+        .or_else(|src| {
+            parse_seq(src, "ObjectProperty:").map(|s| s.map(|_| Section::ObjectProperty))
+        })
+        .or_else(|src| {
+            parse_seq(src, "SubPropertyChain:").map(|s| s.map(|_| Section::SubPropertyChain))
+        })
+        .or_else(|src| parse_seq(src, "DataProperty:").map(|s| s.map(|_| Section::DataProperty)))
+        .or_else(|src| parse_seq(src, "Range:").map(|s| s.map(|_| Section::Range)))
+        .or_else(|src| parse_seq(src, "SubClassOf:").map(|s| s.map(|_| Section::SubClassOf)))
+        .or_else(|src| {
+            parse_seq(src, "DifferentIndividuals:")
+                .map(|s| s.map(|_| Section::DifferentIndividuals))
+        })
+}
+
+fn parse_iri<'s>(src: Input<'s>) -> LexerResult<'s, Located<String>> {
+    // the characters excluded here keep `[<"400"]` and `[<="400"]` lexing as
+    // punctuation followed by a literal
+
+    parse_char(src, '<')
+        .and_then(|r, _| {
+            let (remaining, located) = parse_while(r, |c| {
+                !c.is_whitespace()
+                    && !matches!(c, '<' | '>' | '"' | '{' | '}' | '|' | '^' | '`' | '\\')
+            });
+
+            if located.value.is_empty() {
+                return LexerResult::err(src, LexerErr::UnrecognizedToken);
+            }
+
+            parse_char(remaining, '>').and_then(|_, _| {
+                let len = 2 + located.value.len();
+
+                let (remaining, span) = src.split(len);
+
+                LexerResult::ok(
+                    remaining,
+                    Located::new(located.value.to_string(), span.span),
+                )
+            })
+        })
+        .or_else(|_| LexerResult::err(src, LexerErr::UnrecognizedToken))
+}
+
+fn parse_punctuation<'s>(src: Input<'s>) -> LexerResult<'s, Located<Punctuation>> {
     LexerResult::err(src, LexerErr::UnrecognizedToken)
         .or_else(|src| parse_char(src, '(').map(|l| l.map(|_| Punctuation::OpenParen)))
         .or_else(|src| parse_char(src, ')').map(|l| l.map(|_| Punctuation::CloseParen)))
@@ -225,7 +400,7 @@ fn parse_punctuation<'s>(src: Span<'s>) -> LexerResult<'s, Located<'s, Punctuati
         .or_else(|src| parse_char(src, '>').map(|l| l.map(|_| Punctuation::GreaterThan)))
 }
 
-fn parse_identifier<'s>(src: Span<'s>) -> LexerResult<'s, Located<'s, String>> {
+fn parse_identifier<'s>(src: Input<'s>) -> LexerResult<'s, Located<String>> {
     parse_if(src, |c| c.is_uppercase()).and_then(|r, c| {
         let mut prev = c.value;
 
@@ -238,7 +413,7 @@ fn parse_identifier<'s>(src: Span<'s>) -> LexerResult<'s, Located<'s, String>> {
             should_continue
         });
 
-        let len = rest.value.len() + 1;
+        let len = c.value.len_utf8() + rest.value.len();
 
         let (remaining, span) = src.split(len);
 
@@ -246,13 +421,16 @@ fn parse_identifier<'s>(src: Span<'s>) -> LexerResult<'s, Located<'s, String>> {
             LexerResult::err(src, LexerErr::UnrecognizedToken)
         } else {
             parse_if(remaining, |c| !c.is_alphabetic() && c != '_').and_then(|_, _| {
-                LexerResult::ok(remaining, Located::new(span.fragment().to_string(), span))
+                LexerResult::ok(
+                    remaining,
+                    Located::new(span.fragment().to_string(), span.span),
+                )
             })
         }
     })
 }
 
-fn parse_class<'s>(src: Span<'s>) -> LexerResult<'s, Located<'s, String>> {
+fn parse_class<'s>(src: Input<'s>) -> LexerResult<'s, Located<String>> {
     parse_if(src, |c| c.is_uppercase()).and_then(|_, _| {
         let mut prev = '_';
 
@@ -275,7 +453,7 @@ fn parse_class<'s>(src: Span<'s>) -> LexerResult<'s, Located<'s, String>> {
     })
 }
 
-fn parse_property<'s>(src: Span<'s>) -> LexerResult<'s, Located<'s, String>> {
+fn parse_property<'s>(src: Input<'s>) -> LexerResult<'s, Located<String>> {
     LexerResult::err(src, LexerErr::UnrecognizedToken)
         .or_else(|src| {
             parse_seq(src, "has")
@@ -285,7 +463,10 @@ fn parse_property<'s>(src: Span<'s>) -> LexerResult<'s, Located<'s, String>> {
 
                     let (remaining, span) = src.split(len);
 
-                    LexerResult::ok(remaining, Located::new(span.fragment().to_string(), span))
+                    LexerResult::ok(
+                        remaining,
+                        Located::new(span.fragment().to_string(), span.span),
+                    )
                 })
         })
         .or_else(|_| {
@@ -297,7 +478,10 @@ fn parse_property<'s>(src: Span<'s>) -> LexerResult<'s, Located<'s, String>> {
 
                         let (remaining, span) = src.split(len);
 
-                        LexerResult::ok(remaining, Located::new(span.fragment().to_string(), span))
+                        LexerResult::ok(
+                            remaining,
+                            Located::new(span.fragment().to_string(), span.span),
+                        )
                     } else {
                         LexerResult::err(src, LexerErr::UnrecognizedToken)
                     }
@@ -305,8 +489,46 @@ fn parse_property<'s>(src: Span<'s>) -> LexerResult<'s, Located<'s, String>> {
         })
 }
 
-fn parse_cardinality<'s>(src: Span<'s>) -> LexerResult<'s, Located<'s, usize>> {
-    let (remaining, located) = parse_while(src, |c| c.is_digit(10));
+fn parse_annotation_property<'s>(src: Input<'s>) -> LexerResult<'s, Located<String>> {
+    // prefixed names such as `rdfs:label` or `dc:creator`
+
+    parse_if(src, |c| c.is_ascii_lowercase())
+        .and_then(|r, _| {
+            let (r, _) = parse_while(r, |c| c.is_ascii_alphanumeric() || c == '_' || c == '-');
+            parse_char(r, ':')
+        })
+        .and_then(|r, _| parse_if(r, |c| c.is_alphabetic()))
+        .and_then(|r, _| {
+            let (r, _) = parse_while(r, |c| c.is_alphanumeric() || c == '_');
+
+            let (remaining, span) = src.split(r.span.start() - src.span.start());
+
+            LexerResult::ok(
+                remaining,
+                Located::new(span.fragment().to_string(), span.span),
+            )
+        })
+        .or_else(|_| LexerResult::err(src, LexerErr::UnrecognizedToken))
+}
+
+fn parse_language_tag<'s>(src: Input<'s>) -> LexerResult<'s, Located<String>> {
+    parse_char(src, '@')
+        .and_then(|r, _| parse_if(r, |c| c.is_ascii_alphabetic()))
+        .and_then(|r, _| {
+            let (r, _) = parse_while(r, |c| c.is_ascii_alphanumeric() || c == '-');
+
+            let (remaining, span) = src.split(r.span.start() - src.span.start());
+
+            LexerResult::ok(
+                remaining,
+                Located::new(span.fragment()[1..].to_string(), span.span),
+            )
+        })
+        .or_else(|_| LexerResult::err(src, LexerErr::UnrecognizedToken))
+}
+
+fn parse_cardinality<'s>(src: Input<'s>) -> LexerResult<'s, Located<usize>> {
+    let (remaining, located) = parse_while(src, |c| c.is_ascii_digit());
 
     if located.value.is_empty() {
         LexerResult::err(src, LexerErr::UnrecognizedToken)
@@ -316,7 +538,7 @@ fn parse_cardinality<'s>(src: Span<'s>) -> LexerResult<'s, Located<'s, usize>> {
     }
 }
 
-fn parse_literal<'s>(src: Span<'s>) -> LexerResult<'s, Located<'s, String>> {
+fn parse_literal<'s>(src: Input<'s>) -> LexerResult<'s, Located<String>> {
     parse_char(src, '"').and_then(|r, _| {
         let (remaining, located) = parse_while(r, |c| c != '"' && c != '\n');
 
@@ -325,42 +547,70 @@ fn parse_literal<'s>(src: Span<'s>) -> LexerResult<'s, Located<'s, String>> {
 
             let (remaining, span) = src.split(len);
 
-            LexerResult::ok(remaining, Located::new(located.value.to_string(), span))
+            LexerResult::ok(
+                remaining,
+                Located::new(located.value.to_string(), span.span),
+            )
         })
     })
 }
 
-fn parse_token<'s>(src: Span<'s>) -> LexerResult<'s, Located<'s, TokenType>> {
+fn parse_token<'s>(src: Input<'s>) -> LexerResult<'s, Located<TokenType>> {
     LexerResult::err(src, LexerErr::UnrecognizedToken)
-        .or_else(|src| parse_keyword(src).map(|l| l.map(|k| TokenType::Keyword(k))))
-        .or_else(|src| parse_datatype(src).map(|l| l.map(|d| TokenType::Datatype(d))))
-        .or_else(|src| parse_punctuation(src).map(|l| l.map(|p| TokenType::Punctuation(p))))
-        .or_else(|src| parse_class(src).map(|l| l.map(|c| TokenType::ClassIdentifier(c))))
-        .or_else(|src| parse_property(src).map(|l| l.map(|p| TokenType::PropertyIdentifier(p))))
-        .or_else(|src| parse_cardinality(src).map(|l| l.map(|c| TokenType::Cardinality(c))))
-        .or_else(|src| parse_literal(src).map(|l| l.map(|s| TokenType::Literal(s))))
+        .or_else(|src| parse_section(src).map(|l| l.map(TokenType::Section)))
+        .or_else(|src| parse_iri(src).map(|l| l.map(TokenType::Iri)))
+        .or_else(|src| {
+            parse_annotation_property(src).map(|l| l.map(TokenType::AnnotationPropertyIdentifier))
+        })
+        .or_else(|src| parse_keyword(src).map(|l| l.map(TokenType::Keyword)))
+        .or_else(|src| parse_datatype(src).map(|l| l.map(TokenType::Datatype)))
+        .or_else(|src| parse_punctuation(src).map(|l| l.map(TokenType::Punctuation)))
+        .or_else(|src| parse_class(src).map(|l| l.map(TokenType::ClassIdentifier)))
+        .or_else(|src| parse_property(src).map(|l| l.map(TokenType::PropertyIdentifier)))
+        .or_else(|src| parse_cardinality(src).map(|l| l.map(TokenType::Cardinality)))
+        .or_else(|src| parse_literal(src).map(|l| l.map(TokenType::Literal)))
+        .or_else(|src| parse_language_tag(src).map(|l| l.map(TokenType::LanguageTag)))
 }
 
 #[derive(Debug, Clone)]
 pub enum Token {
+    Section(Section),
+    Iri(String),
+    LanguageTag(String),
     Keyword(Keyword),
     Datatype(Datatype),
     Punctuation(Punctuation),
     Cardinality(usize),
     ClassIdentifier { index: usize },
     PropertyIdentifier { index: usize },
+    AnnotationPropertyIdentifier { index: usize },
     Literal { index: usize },
 }
 
-pub fn parse<'s>(
-    src: &'s Source,
-) -> Result<(SymbolTable, Vec<Located<'s, Token>>), Located<'s, LexerErr>> {
-    let mut src = skip_whitespace(Span::from(src));
-    let mut table = SymbolTable::new();
+pub fn parse(
+    map: &SourceMap,
+    file_id: FileId,
+    table: &mut SymbolTable,
+) -> Result<Vec<Located<Token>>, Located<LexerErr>> {
+    let input = Input {
+        content: &map.get(file_id).content,
+        span: map.span(file_id),
+    };
+    let mut src = skip_whitespace(input);
     let mut tokens = Vec::new();
 
     while let Ok((remaining, token_type)) = parse_token(src).result() {
         let located = match token_type.value {
+            TokenType::Section(s) => Located::new(Token::Section(s), token_type.span),
+            TokenType::Iri(i) => Located::new(Token::Iri(i), token_type.span),
+            TokenType::LanguageTag(l) => Located::new(Token::LanguageTag(l), token_type.span),
+            TokenType::AnnotationPropertyIdentifier(a) => {
+                let index = table.get_or_insert(Type::AnnotationProperty, a);
+                Located::new(
+                    Token::AnnotationPropertyIdentifier { index },
+                    token_type.span,
+                )
+            }
             TokenType::Keyword(k) => Located::new(Token::Keyword(k), token_type.span),
             TokenType::Datatype(d) => Located::new(Token::Datatype(d), token_type.span),
             TokenType::Punctuation(p) => Located::new(Token::Punctuation(p), token_type.span),
@@ -384,8 +634,8 @@ pub fn parse<'s>(
     }
 
     if !src.fragment().is_empty() {
-        return Err(Located::new(LexerErr::UnrecognizedToken, src));
+        return Err(Located::new(LexerErr::UnrecognizedToken, src.span));
     }
 
-    Ok((table, tokens))
+    Ok(tokens)
 }
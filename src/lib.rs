@@ -0,0 +1,22 @@
+//! Lexer, parser and semantic analyses for ontologies written in a subset of
+//! the Manchester OWL syntax.
+
+pub mod checks;
+pub mod classifier;
+pub mod cst;
+pub mod diagnostic;
+pub mod functional;
+pub mod hierarchy;
+pub mod imports;
+pub mod interval;
+pub mod lexer;
+pub mod lint;
+pub mod normalize;
+pub mod parser;
+pub mod printer;
+pub mod rdf;
+pub mod reasoner;
+pub mod rename;
+pub mod span;
+pub mod table;
+pub mod tableau;
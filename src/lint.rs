@@ -0,0 +1,251 @@
+use std::collections::BTreeSet;
+
+use crate::{
+    diagnostic::Diagnostic,
+    imports::Loaded,
+    lexer::Token,
+    parser::{
+        Class, ClassDecl, ClassIdentifier, Frame, ObjectDescription, Property, PropertyDescription,
+    },
+    span::{Located, Span},
+    table::{SymbolTable, Type},
+};
+
+/// Classes by role, as indexes into the symbol table.
+#[derive(Default)]
+struct Usage {
+    /// Subjects of a declaration or of a `Class:` frame.
+    declared: BTreeSet<usize>,
+    /// Classes used inside a description or an axiom.
+    referenced: BTreeSet<usize>,
+    /// Members of enumerations and fillers of `VALUE`, which are not classes
+    /// to be declared.
+    individuals: BTreeSet<usize>,
+}
+
+/// Warns about classes referenced but never declared, suggesting a class
+/// of the symbol table with a similar name, and about declared classes
+/// never referenced.
+pub fn lint(files: &[Loaded], table: &SymbolTable) -> Vec<Diagnostic> {
+    let mut usage = Usage::default();
+
+    for loaded in files {
+        for frame in &loaded.ontology.frames {
+            usage.frame(&frame.value);
+        }
+    }
+
+    let mut diagnostics = Vec::new();
+
+    for &index in usage.referenced.difference(&usage.declared) {
+        // members of individual axioms are recorded as such in the table
+        let individual = table
+            .get(index)
+            .is_some_and(|symbol| matches!(symbol.type_(), Type::Individual));
+
+        if individual || usage.individuals.contains(&index) {
+            continue;
+        }
+
+        let Some(span) = first_occurrence(files, index) else {
+            continue;
+        };
+
+        let name = table.get(index).map_or("?", |s| s.id());
+        let mut message = format!("class '{}' is used but never declared", name);
+
+        if let Some(suggestion) = suggest(index, &usage.declared, table) {
+            message += &format!(", did you mean '{}'?", suggestion);
+        }
+
+        diagnostics.push(Diagnostic::warning(span, message));
+    }
+
+    for &index in usage.declared.difference(&usage.referenced) {
+        let Some(span) = first_occurrence(files, index) else {
+            continue;
+        };
+
+        let name = table.get(index).map_or("?", |s| s.id());
+        let message = format!("class '{}' is declared but never used", name);
+        diagnostics.push(Diagnostic::warning(span, message));
+    }
+
+    diagnostics
+}
+
+impl Usage {
+    fn frame(&mut self, frame: &Frame) {
+        match frame {
+            Frame::Class {
+                class,
+                super_classes,
+                ..
+            } => {
+                self.declared.insert(class.index);
+
+                for super_class in super_classes {
+                    self.class(&super_class.value);
+                }
+            }
+            Frame::Decl { decl, .. } => match &decl.value {
+                ClassDecl::Defined(class, properties) | ClassDecl::Primitive(class, properties) => {
+                    self.declared.insert(class.index);
+                    self.properties(properties);
+                }
+                ClassDecl::Enumerated(classes) => self.individuals(classes),
+                ClassDecl::Union(classes) => self.classes(classes),
+            },
+            Frame::DisjointClasses(misc) | Frame::EquivalentClasses(misc) => {
+                for class in &misc.members {
+                    self.class(class);
+                }
+            }
+            Frame::AnnotationProperty { .. }
+            | Frame::ObjectProperty { .. }
+            | Frame::DataProperty { .. }
+            | Frame::DisjointProperties(_)
+            | Frame::SameIndividual(_)
+            | Frame::DifferentIndividuals(_) => {}
+        }
+    }
+
+    fn class(&mut self, class: &Class) {
+        match class {
+            Class::Identifier(class) => {
+                self.referenced.insert(class.index);
+            }
+            Class::Defined(class, properties) => {
+                self.referenced.insert(class.index);
+                self.properties(properties);
+            }
+            Class::Enumerated(classes) => self.individuals(classes),
+            Class::Union(classes) => self.classes(classes),
+            Class::Complement(class) => self.class(class),
+        }
+    }
+
+    fn classes(&mut self, classes: &[ClassIdentifier]) {
+        self.referenced
+            .extend(classes.iter().map(|class| class.index));
+    }
+
+    fn individuals(&mut self, individuals: &[ClassIdentifier]) {
+        self.individuals
+            .extend(individuals.iter().map(|individual| individual.index));
+    }
+
+    fn properties(&mut self, properties: &[Located<Property>]) {
+        for property in properties {
+            match &property.value.description {
+                PropertyDescription::Object(description) => match description {
+                    ObjectDescription::Some(class)
+                    | ObjectDescription::Only(class)
+                    | ObjectDescription::Min(_, class)
+                    | ObjectDescription::Max(_, class)
+                    | ObjectDescription::Exactly(_, class) => self.class(class),
+                    ObjectDescription::Value(individual) => {
+                        self.individuals.insert(individual.index);
+                    }
+                },
+                PropertyDescription::Data(_) => {}
+            }
+        }
+    }
+}
+
+fn first_occurrence(files: &[Loaded], index: usize) -> Option<Span> {
+    files
+        .iter()
+        .flat_map(|loaded| &loaded.tokens)
+        .find(|token| matches!(token.value, Token::ClassIdentifier { index: i } if i == index))
+        .map(|token| token.span)
+}
+
+/// The other class of the table closest to the one at `index`, if it is
+/// close enough to be a misspelling of it. Declared classes win ties, so
+/// the suggestion is the one most likely meant.
+fn suggest<'t>(
+    index: usize,
+    declared: &BTreeSet<usize>,
+    table: &'t SymbolTable,
+) -> Option<&'t str> {
+    let name = table.get(index)?.id();
+    let threshold = (name.chars().count() / 4).clamp(1, 3);
+
+    table
+        .symbols()
+        .iter()
+        .filter(|(&other, symbol)| other != index && matches!(symbol.type_(), Type::Class))
+        .map(|(other, symbol)| {
+            let distance = edit_distance(name, symbol.id());
+            (distance, !declared.contains(other), symbol.id())
+        })
+        .filter(|(distance, ..)| *distance <= threshold)
+        .min()
+        .map(|(.., id)| id)
+}
+
+/// Levenshtein distance between two strings, counted in characters.
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b = b.chars().collect::<Vec<_>>();
+    let mut previous = (0..=b.len()).collect::<Vec<_>>();
+
+    for (i, ca) in a.chars().enumerate() {
+        let mut current = vec![i + 1];
+
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(ca != *cb);
+            let insertion = current[j] + 1;
+            let deletion = previous[j + 1] + 1;
+            current.push(substitution.min(insertion).min(deletion));
+        }
+
+        previous = current;
+    }
+
+    previous[b.len()]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn edit_distance_counts_characters() {
+        assert_eq!(edit_distance("Cheese", "Cheese"), 0);
+        assert_eq!(edit_distance("Cheese", "Chese"), 1);
+        assert_eq!(edit_distance("Cheese", "Cheeses"), 1);
+        assert_eq!(edit_distance("Tomato", "Tomata"), 1);
+        assert_eq!(edit_distance("", "Pão"), 3);
+        assert_eq!(edit_distance("Pão", "Pao"), 1);
+    }
+
+    #[test]
+    fn suggests_only_names_within_the_threshold() {
+        let mut table = SymbolTable::new();
+        let misspelled = table.get_or_insert(Type::Class, "Mozarela".to_string());
+        table.get_or_insert(Type::Class, "Mozzarella".to_string());
+        table.get_or_insert(Type::Property(None), "Mozarelas".to_string());
+        let short = table.get_or_insert(Type::Class, "Egg".to_string());
+        table.get_or_insert(Type::Class, "Ham".to_string());
+
+        let declared = BTreeSet::new();
+        assert_eq!(suggest(misspelled, &declared, &table), Some("Mozzarella"));
+        assert_eq!(suggest(short, &declared, &table), None);
+    }
+
+    #[test]
+    fn prefers_declared_classes_on_ties() {
+        let mut table = SymbolTable::new();
+        let misspelled = table.get_or_insert(Type::Class, "Chese".to_string());
+        let cheese = table.get_or_insert(Type::Class, "Cheese".to_string());
+        let chess = table.get_or_insert(Type::Class, "Chess".to_string());
+
+        let declared = BTreeSet::from([chess]);
+        assert_eq!(suggest(misspelled, &declared, &table), Some("Chess"));
+
+        let declared = BTreeSet::from([cheese]);
+        assert_eq!(suggest(misspelled, &declared, &table), Some("Cheese"));
+    }
+}
@@ -1,23 +1,59 @@
 use std::{
     env::args,
-    fs::{read_to_string, File},
+    fs::{read_to_string, write, File},
     io::{stdout, BufWriter, Write},
+    path::Path,
+    process::exit,
 };
 
-use span::Source;
-use table::SymbolTable;
-
-mod lexer;
-mod parser;
-mod span;
-mod table;
+use compowl::{
+    checks, classifier, cst, functional,
+    hierarchy::Hierarchy,
+    imports::{Catalog, LoadErr, Loader},
+    lexer, lint, normalize,
+    parser::{self, Frame},
+    printer::{self, Style},
+    rdf, reasoner, rename,
+    span::{ColumnUnit, SourceMap},
+    table::SymbolTable,
+    tableau::Tableau,
+};
 
+#[derive(Clone, Copy)]
 enum OutputType {
     File,
     Stdout,
     FileAndStdout,
 }
 
+/// Syntax written by the `export` mode.
+#[derive(Clone, Copy)]
+enum ExportFormat {
+    Functional,
+    Turtle,
+    NTriples,
+}
+
+impl ExportFormat {
+    fn extension(&self) -> &'static str {
+        match self {
+            ExportFormat::Functional => "ofn",
+            ExportFormat::Turtle => "ttl",
+            ExportFormat::NTriples => "nt",
+        }
+    }
+}
+
+/// Analyses enabled by command line options.
+#[derive(Clone, Copy, Default)]
+struct Options {
+    lint: bool,
+    hierarchy: bool,
+    classify: bool,
+    satisfiability: bool,
+    normalize: bool,
+}
+
 enum Output {
     File(BufWriter<File>),
     Stdout,
@@ -43,12 +79,41 @@ impl Write for Output {
 }
 
 fn usage(name: &str) {
-    println!("Usage: {} <output> <file1> <file2> ...", name);
+    println!("Usage: {} <output> [options] <file1> <file2> ...", name);
     println!("    <output> is: -f OR -t OR -ft");
     println!("        -f: output tokens and table to <file>.output for each given file");
     println!("        -t: output tokens and table to stdout for each given file");
     println!("        -ft: both -f and -t");
+    println!("    [options] are:");
+    println!("        --utf16-columns: count columns in UTF-16 code units instead of characters");
+    println!("        --merge: treat all given files as one ontology, reported to merged.output");
+    println!(
+        "        --catalog=<file>: resolve imported IRIs through the '<iri> path' lines of <file>"
+    );
+    println!("        --lint: warn about undeclared and unused classes");
+    println!("        --hierarchy: include the told class hierarchy in the output");
+    println!("        --classify: include the hierarchy inferred by the EL reasoner");
+    println!("        --satisfiability: include the consistency and unsatisfiable classes");
+    println!("        --normalize: print the normalized descriptions after each frame");
     println!("    <file1> <file2> ...: files to parse");
+    println!(
+        "Usage: {} fmt [--check] [--indent=<n>] [--width=<n>] <file1> ...",
+        name
+    );
+    println!("        --check: only report the files that are not formatted");
+    println!("        --indent=<n>: spaces per level of indentation (default 4)");
+    println!("        --width=<n>: line width past which descriptions are broken (default 80)");
+    println!(
+        "Usage: {} export [--format=<format>] [--namespace=<iri>] <file1> ...",
+        name
+    );
+    println!("        writes each file in OWL to <file>.ofn, <file>.ttl or <file>.nt");
+    println!("        --format=<format>: functional (default), turtle or ntriples");
+    println!(
+        "        --namespace=<iri>: namespace of the entities (default: the ontology IRI and '#')"
+    );
+    println!("Usage: {} rename <old> <new> <file1> ...", name);
+    println!("        renames the class or property <old> to <new> in every given file");
 }
 
 fn main() {
@@ -59,6 +124,21 @@ fn main() {
     //     "file6.txt".to_string(),
     // ];
 
+    if args.get(1).map(|s| s.as_str()) == Some("fmt") {
+        format(&args);
+        return;
+    }
+
+    if args.get(1).map(|s| s.as_str()) == Some("export") {
+        export(&args);
+        return;
+    }
+
+    if args.get(1).map(|s| s.as_str()) == Some("rename") {
+        rename(&args);
+        return;
+    }
+
     let output_type = match args.get(1).map(|s| s.as_str()) {
         Some("-f") => OutputType::File,
         Some("-t") => OutputType::Stdout,
@@ -75,7 +155,40 @@ fn main() {
         }
     };
 
-    let files = &args[2..];
+    let (flags, files): (Vec<_>, Vec<_>) = args[2..].iter().partition(|arg| arg.starts_with("--"));
+
+    let mut column_unit = ColumnUnit::Char;
+    let mut merge = false;
+    let mut options = Options::default();
+    let mut catalog = Catalog::new();
+
+    for option in flags {
+        if let Some(path) = option.strip_prefix("--catalog=") {
+            catalog = match Catalog::load(Path::new(path)) {
+                Ok(catalog) => catalog,
+                Err(err) => {
+                    println!("Could not read catalog {}: {}", path, err);
+                    return;
+                }
+            };
+            continue;
+        }
+
+        match option.as_str() {
+            "--utf16-columns" => column_unit = ColumnUnit::Utf16,
+            "--merge" => merge = true,
+            "--lint" => options.lint = true,
+            "--hierarchy" => options.hierarchy = true,
+            "--classify" => options.classify = true,
+            "--satisfiability" => options.satisfiability = true,
+            "--normalize" => options.normalize = true,
+            _ => {
+                println!("Invalid option: {}", option);
+                usage(&args[0]);
+                return;
+            }
+        }
+    }
 
     if files.is_empty() {
         println!("No files given");
@@ -83,126 +196,500 @@ fn main() {
         return;
     }
 
-    for filename in files {
-        println!("Parsing {}", filename);
-        println!("{:-<1$}", "", filename.len() + 8);
+    let mut map = SourceMap::new().with_column_unit(column_unit);
+
+    if merge {
+        analyze(&mut map, &catalog, &files, "merged", output_type, options);
+    } else {
+        for filename in files {
+            analyze(
+                &mut map,
+                &catalog,
+                &[filename],
+                filename,
+                output_type,
+                options,
+            );
+        }
+    }
+}
+
+/// `fmt` mode: rewrites each file in canonical Manchester syntax, or with
+/// `--check` only reports the ones that differ from it and exits with 1.
+fn format(args: &[String]) {
+    let (flags, files): (Vec<_>, Vec<_>) = args[2..].iter().partition(|arg| arg.starts_with("--"));
+
+    let mut check = false;
+    let mut style = Style::default();
+
+    for option in flags {
+        match option
+            .split_once('=')
+            .map(|(key, value)| (key, value.parse::<usize>()))
+        {
+            Some(("--indent", Ok(n))) => style.indent = n,
+            Some(("--width", Ok(n))) => style.width = n,
+            None if option == "--check" => check = true,
+            _ => {
+                println!("Invalid option: {}", option);
+                usage(&args[0]);
+                exit(1);
+            }
+        }
+    }
+
+    if files.is_empty() {
+        println!("No files given");
+        usage(&args[0]);
+        exit(1);
+    }
 
+    let mut map = SourceMap::new();
+    let mut failed = false;
+
+    for filename in files {
         let content = match read_to_string(filename) {
             Ok(content) => content,
             Err(err) => {
-                println!("{}: {}", filename, err);
-                println!("Could not read file {}", filename);
-                println!("{:-<1$}", "", filename.len() + 20);
+                println!("{}: {}\nCould not read file {}", filename, err, filename);
+                failed = true;
                 continue;
             }
         };
 
-        let src = Source {
-            filename: filename.to_string(),
-            content,
+        let file_id = map.add(filename.to_string(), content.clone());
+        let mut table = SymbolTable::new();
+
+        let formatted = lexer::parse(&map, file_id, &mut table)
+            .map_err(LoadErr::Lexer)
+            .and_then(|tokens| parser::parse(&tokens, &mut table).map_err(LoadErr::Parser))
+            .map(|ontology| printer::ontology(&ontology, &table, style));
+
+        match formatted {
+            Err(err) => {
+                println!("{}", err.describe(&map));
+                failed = true;
+            }
+            Ok(formatted) if formatted == content => {}
+            Ok(_) if check => {
+                println!("{} is not formatted", filename);
+                failed = true;
+            }
+            Ok(formatted) => match write(filename, formatted) {
+                Ok(()) => println!("Formatted {}", filename),
+                Err(err) => {
+                    println!("{}: {}\nCould not write file {}", filename, err, filename);
+                    failed = true;
+                }
+            },
+        }
+    }
+
+    if failed {
+        exit(1);
+    }
+}
+
+/// `export` mode: writes each file in OWL 2 Functional-Style syntax, Turtle
+/// or N-Triples, so it can be loaded by other OWL tools.
+fn export(args: &[String]) {
+    let (flags, files): (Vec<_>, Vec<_>) = args[2..].iter().partition(|arg| arg.starts_with("--"));
+
+    let mut namespace = None;
+    let mut format = ExportFormat::Functional;
+
+    for option in flags {
+        match option.split_once('=') {
+            Some(("--namespace", iri)) if !iri.is_empty() => namespace = Some(iri),
+            Some(("--format", "functional")) => format = ExportFormat::Functional,
+            Some(("--format", "turtle")) => format = ExportFormat::Turtle,
+            Some(("--format", "ntriples")) => format = ExportFormat::NTriples,
+            _ => {
+                println!("Invalid option: {}", option);
+                usage(&args[0]);
+                return;
+            }
+        }
+    }
+
+    if files.is_empty() {
+        println!("No files given");
+        usage(&args[0]);
+        return;
+    }
+
+    let mut map = SourceMap::new();
+    let mut failed = false;
+
+    for filename in files {
+        let content = match read_to_string(filename) {
+            Ok(content) => content,
+            Err(err) => {
+                println!("{}: {}\nCould not read file {}", filename, err, filename);
+                failed = true;
+                continue;
+            }
         };
 
-        match lexer::parse(&src) {
-            Ok((mut table, tokens)) => match parser::parse(&tokens, &mut table) {
-                Ok(decls) => {
-                    let mut out = match output_type {
-                        OutputType::File => Output::File(BufWriter::new(
-                            File::create(format!("{}.output", filename))
-                                .expect(&format!("Could not create file {}.output", filename)),
-                        )),
-                        OutputType::Stdout => Output::Stdout,
-                        OutputType::FileAndStdout => Output::FileAndStdout(BufWriter::new(
-                            File::create(format!("{}.output", filename))
-                                .expect(&format!("Could not create file {}.output", filename)),
-                        )),
-                    };
-
-                    for token in &tokens {
-                        writeln!(out, "{:?}", token)
-                            .expect(&format!("Could not write to file {}.output", filename));
-                    }
+        let file_id = map.add(filename.to_string(), content);
+        let mut table = SymbolTable::new();
 
-                    writeln!(out).expect("Could not write to file");
+        let exported = lexer::parse(&map, file_id, &mut table)
+            .map_err(LoadErr::Lexer)
+            .and_then(|tokens| parser::parse(&tokens, &mut table).map_err(LoadErr::Parser))
+            .map(|ontology| {
+                let namespace = namespace.map_or_else(
+                    || functional::namespace(&ontology),
+                    |namespace| namespace.to_string(),
+                );
 
-                    out.flush()
-                        .expect(&format!("Could not write to file {}.output", filename));
+                match format {
+                    ExportFormat::Functional => functional::ontology(&ontology, &table, &namespace),
+                    ExportFormat::Turtle => {
+                        rdf::turtle(&rdf::triples(&ontology, &table, &namespace), &namespace)
+                    }
+                    ExportFormat::NTriples => {
+                        rdf::ntriples(&rdf::triples(&ontology, &table, &namespace))
+                    }
+                }
+            });
 
-                    for decl in &decls {
-                        writeln!(
-                            out,
-                            "{}:\n{}\n--\n{:#?}\n",
-                            decl.span.location(),
-                            decl.span.fragment(),
-                            decl.value,
-                        )
-                        .expect(&format!("Could not write to file {}.output", filename));
+        match exported {
+            Err(err) => {
+                println!("{}", err.describe(&map));
+                failed = true;
+            }
+            Ok(exported) => {
+                let output = format!("{}.{}", filename, format.extension());
+
+                match write(&output, exported) {
+                    Ok(()) => println!("Exported {} to {}", filename, output),
+                    Err(err) => {
+                        println!("{}: {}\nCould not write file {}", output, err, output);
+                        failed = true;
                     }
+                }
+            }
+        }
+    }
 
-                    writeln!(out).expect("Could not write to file");
+    if failed {
+        exit(1);
+    }
+}
+
+/// `rename` mode: renames a class or property in every given file, which
+/// share one symbol table so a collision in any of them is refused.
+fn rename(args: &[String]) {
+    let (old, new, files) = match &args[2..] {
+        [old, new, files @ ..] if !files.is_empty() => (old, new, files),
+        _ => {
+            println!("Expected the old name, the new name and the files to rename in");
+            usage(&args[0]);
+            return;
+        }
+    };
+
+    let mut map = SourceMap::new();
+    let mut table = SymbolTable::new();
+    let mut trees = Vec::new();
+
+    for filename in files {
+        let content = match read_to_string(filename) {
+            Ok(content) => content,
+            Err(err) => {
+                println!("{}: {}\nCould not read file {}", filename, err, filename);
+                exit(1);
+            }
+        };
+
+        let file_id = map.add(filename.to_string(), content);
+
+        let parsed = lexer::parse(&map, file_id, &mut table)
+            .map_err(LoadErr::Lexer)
+            .and_then(|tokens| {
+                let ontology = parser::parse(&tokens, &mut table).map_err(LoadErr::Parser)?;
+                Ok((tokens, ontology))
+            });
+
+        match parsed {
+            Ok((tokens, ontology)) => {
+                let root = cst::build(&map.get(file_id).content, &tokens, &ontology);
+                trees.push((filename, root, tokens));
+            }
+            Err(err) => {
+                println!("{}", err.describe(&map));
+                exit(1);
+            }
+        }
+    }
 
-                    write_table(&mut out, &table);
+    let index = match rename::check(&table, old, new) {
+        Ok(index) => index,
+        Err(err) => {
+            println!("Could not rename: {}", err.describe());
+            exit(1);
+        }
+    };
+
+    let mut failed = false;
+
+    for (filename, root, tokens) in trees {
+        let (renamed, count) = rename::apply(&root, &tokens, index, new);
+
+        if count == 0 {
+            continue;
+        }
 
-                    out.flush()
-                        .expect(&format!("Could not write to file {}.output", filename));
+        match write(filename, renamed.text()) {
+            Ok(()) => println!("Renamed {} occurrence(s) in {}", count, filename),
+            Err(err) => {
+                println!("{}: {}\nCould not write file {}", filename, err, filename);
+                failed = true;
+            }
+        }
+    }
+
+    if failed {
+        exit(1);
+    }
+}
+
+/// Parses the given files and their imports as a single ontology sharing
+/// one symbol table, and writes the report to `<name>.output` and/or stdout.
+fn analyze(
+    map: &mut SourceMap,
+    catalog: &Catalog,
+    filenames: &[&String],
+    name: &str,
+    output_type: OutputType,
+    options: Options,
+) {
+    println!("Parsing {}", name);
+    println!("{:-<1$}", "", name.len() + 8);
+
+    let mut table = SymbolTable::new();
+    let mut loader = Loader::new(catalog);
+
+    for filename in filenames {
+        if let Err(err) = loader.load(map, &mut table, filename) {
+            println!("{}", err.describe(map));
+            println!("Finished parsing {} with failure", name);
+            println!("{:-<1$}", "", name.len() + 30);
+            return;
+        }
+    }
+
+    for loaded in &loader.files {
+        let ontologies = loader.files.iter().map(|loaded| &loaded.ontology);
+
+        for diagnostic in checks::check(&loaded.ontology, ontologies, &table) {
+            println!("{}", diagnostic.describe(map));
+        }
+    }
 
-                    println!("Finished parsing {} with success", filename);
-                    println!("{:-<1$}", "", filename.len() + 30);
+    let hierarchy = Hierarchy::new(loader.files.iter().map(|loaded| &loaded.ontology));
+
+    for diagnostic in checks::check_cycles(&hierarchy, &table) {
+        println!("{}", diagnostic.describe(map));
+    }
+
+    for diagnostic in checks::check_duplicates(loader.files.iter().map(|loaded| &loaded.ontology)) {
+        println!("{}", diagnostic.describe(map));
+    }
+
+    if options.lint {
+        for diagnostic in lint::lint(&loader.files, &table) {
+            println!("{}", diagnostic.describe(map));
+        }
+    }
+
+    let mut out = match output_type {
+        OutputType::File => Output::File(BufWriter::new(
+            File::create(format!("{}.output", name))
+                .unwrap_or_else(|_| panic!("Could not create file {}.output", name)),
+        )),
+        OutputType::Stdout => Output::Stdout,
+        OutputType::FileAndStdout => Output::FileAndStdout(BufWriter::new(
+            File::create(format!("{}.output", name))
+                .unwrap_or_else(|_| panic!("Could not create file {}.output", name)),
+        )),
+    };
+
+    for loaded in &loader.files {
+        for token in &loaded.tokens {
+            writeln!(out, "{}: {:?}", map.location(token.span), token.value)
+                .unwrap_or_else(|_| panic!("Could not write to file {}.output", name));
+        }
+
+        writeln!(out).expect("Could not write to file");
+
+        out.flush()
+            .unwrap_or_else(|_| panic!("Could not write to file {}.output", name));
+
+        let ontology = &loaded.ontology;
+
+        if let Some(iri) = &ontology.iri {
+            writeln!(out, "{}: Ontology: <{}>", map.location(iri.span), iri.value)
+                .unwrap_or_else(|_| panic!("Could not write to file {}.output", name));
+        }
+
+        for import in &ontology.imports {
+            writeln!(
+                out,
+                "{}: Import: <{}>",
+                map.location(import.span),
+                import.value
+            )
+            .unwrap_or_else(|_| panic!("Could not write to file {}.output", name));
+        }
+
+        if ontology.iri.is_some() || !ontology.imports.is_empty() {
+            writeln!(out).expect("Could not write to file");
+        }
+
+        for frame in &ontology.frames {
+            writeln!(
+                out,
+                "{} - {}:\n{}\n--\n{:#?}",
+                map.location(frame.span),
+                map.end_location(frame.span),
+                map.fragment(frame.span),
+                frame.value,
+            )
+            .unwrap_or_else(|_| panic!("Could not write to file {}.output", name));
+
+            if let Frame::Decl { annotations, decl } = &frame.value {
+                let labels = classifier::classify(&decl.value)
+                    .iter()
+                    .map(|label| format!("{:?}", label))
+                    .collect::<Vec<_>>();
+
+                writeln!(out, "Labels: {}", labels.join(", "))
+                    .unwrap_or_else(|_| panic!("Could not write to file {}.output", name));
+
+                // axiom annotations belong to no symbol, so they are listed
+                // here instead of in the table
+                if !annotations.is_empty() {
+                    let annotations = annotations
+                        .iter()
+                        .map(|annotation| annotation.describe(&table))
+                        .collect::<Vec<_>>();
+
+                    writeln!(out, "Annotations: {}", annotations.join(", "))
+                        .unwrap_or_else(|_| panic!("Could not write to file {}.output", name));
                 }
-                Err(err) => {
-                    println!("Parser error at {:?}", err);
-                    println!("Finished parsing {} with failure", filename);
-                    println!("{:-<1$}", "", filename.len() + 30);
+            }
+
+            if options.normalize {
+                for expression in normalize::frame(&frame.value) {
+                    writeln!(
+                        out,
+                        "Normalized: {}",
+                        normalize::render(&expression, &table)
+                    )
+                    .unwrap_or_else(|_| panic!("Could not write to file {}.output", name));
                 }
-            },
-            Err(err) => {
-                println!("Lexer error at {}: {:?}", err.span.location(), err.value);
-                println!("Finished parsing {} with failure", filename);
-                println!("{:-<1$}", "", filename.len() + 30);
             }
+
+            writeln!(out).expect("Could not write to file");
         }
+
+        writeln!(out).expect("Could not write to file");
+    }
+
+    if options.hierarchy {
+        writeln!(out, "Hierarchy:\n{}", hierarchy.render(map, &table))
+            .unwrap_or_else(|_| panic!("Could not write to file {}.output", name));
     }
+
+    if options.classify {
+        let classification = reasoner::classify(loader.files.iter().map(|loaded| &loaded.ontology));
+        writeln!(
+            out,
+            "Classification:\n{}",
+            classification.render(map, &table)
+        )
+        .unwrap_or_else(|_| panic!("Could not write to file {}.output", name));
+    }
+
+    if options.satisfiability {
+        let tableau = Tableau::new(loader.files.iter().map(|loaded| &loaded.ontology));
+        writeln!(out, "Satisfiability:\n{}", tableau.render(map, &table))
+            .unwrap_or_else(|_| panic!("Could not write to file {}.output", name));
+    }
+
+    write_table(&mut out, &table);
+
+    out.flush()
+        .unwrap_or_else(|_| panic!("Could not write to file {}.output", name));
+
+    println!("Finished parsing {} with success", name);
+    println!("{:-<1$}", "", name.len() + 30);
 }
 
 fn write_table(f: &mut impl Write, table: &SymbolTable) {
     let mut symbols = table.symbols().iter().collect::<Vec<_>>();
-    symbols.sort_by(|(a, _), (b, _)| a.cmp(b));
+    symbols.sort_by_key(|(index, _)| **index);
 
-    if let Some((index_len, type_len, id_len)) = symbols
+    if let Some((index_len, type_len, id_len, annotations_len)) = symbols
         .iter()
         .map(|(index, symbol)| {
             let index_len = index.to_string().len();
             let type_len = format!("{:?}", symbol.type_()).len();
             let id_len = symbol.id().len();
+            let annotations_len = symbol.annotations().join(", ").len();
 
-            (index_len, type_len, id_len)
+            (index_len, type_len, id_len, annotations_len)
+        })
+        .reduce(|(a1, b1, c1, d1), (a2, b2, c2, d2)| {
+            (a1.max(a2), b1.max(b2), c1.max(c2), d1.max(d2))
         })
-        .reduce(|(a1, b1, c1), (a2, b2, c2)| (a1.max(a2), b1.max(b2), c1.max(c2)))
     {
         let index_title = format!("{:1$}", "Index", index_len);
         let type_title = format!("{:1$}", "Type", type_len);
         let id_title = format!("{:1$}", "ID", id_len);
+        let annotations_title = format!("{:1$}", "Annotations", annotations_len);
 
         let index_len = index_title.len().max(index_len);
         let type_len = type_title.len().max(type_len);
         let id_len = id_title.len().max(id_len);
 
-        writeln!(f, "{:-<1$}", "", index_len + type_len + id_len + 4)
-            .expect("Could not write to file");
-        writeln!(f, "|{}|{}|{}|", index_title, type_title, id_title)
-            .expect("Could not write to file");
-        writeln!(f, "{:-<1$}", "", index_len + type_len + id_len + 4)
-            .expect("Could not write to file");
+        // the annotations column is only shown when some symbol is annotated
+        let (annotations_title, annotations_len) = if annotations_len > 0 {
+            let annotations_len = annotations_title.len().max(annotations_len);
+            (format!("{}|", annotations_title), annotations_len + 1)
+        } else {
+            (String::new(), 0)
+        };
+
+        let width = index_len + type_len + id_len + annotations_len + 4;
+
+        writeln!(f, "{:-<1$}", "", width).expect("Could not write to file");
+        writeln!(
+            f,
+            "|{}|{}|{}|{}",
+            index_title, type_title, id_title, annotations_title
+        )
+        .expect("Could not write to file");
+        writeln!(f, "{:-<1$}", "", width).expect("Could not write to file");
 
         for (index, symbol) in symbols {
             let index = format!("{:<1$}", index, index_len);
             let type_ = format!("{:<1$}", format!("{:?}", symbol.type_()), type_len);
             let id = format!("{:<1$}", symbol.id(), id_len);
+            let annotations = if annotations_len > 0 {
+                let annotations = symbol.annotations().join(", ");
+                format!("{:<1$}|", annotations, annotations_len - 1)
+            } else {
+                String::new()
+            };
 
-            writeln!(f, "|{}|{}|{}|", index, type_, id).expect("Could not write to file");
+            writeln!(f, "|{}|{}|{}|{}", index, type_, id, annotations)
+                .expect("Could not write to file");
         }
 
-        writeln!(f, "{:-<1$}", "", index_len + type_len + id_len + 4)
-            .expect("Could not write to file");
+        writeln!(f, "{:-<1$}", "", width).expect("Could not write to file");
     } else {
         writeln!(f, "No symbols.").expect("Could not write to file");
     }
@@ -0,0 +1,409 @@
+use crate::{
+    lexer::Datatype,
+    parser::{
+        Class, ClassDecl, Data, DataDescription, Frame, ObjectDescription, Property,
+        PropertyDescription, RestrictionType,
+    },
+    table::SymbolTable,
+};
+
+/// Class expression in a canonical form, with symbols by their index in the
+/// symbol table.
+///
+/// Unlike `Class`, intersections and unions can hold any expression, which
+/// negation normal form needs. The derived order is the canonical order of
+/// operands.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Expression {
+    Thing,
+    Nothing,
+    Class(usize),
+    OneOf(Vec<usize>),
+    /// Negation, left in normal form only on named classes, enumerations
+    /// and data restrictions without a dual.
+    Not(Box<Expression>),
+    And(Vec<Expression>),
+    Or(Vec<Expression>),
+    Some(usize, Box<Expression>),
+    Only(usize, Box<Expression>),
+    Value(usize, usize),
+    Min(usize, usize, Box<Expression>),
+    Max(usize, usize, Box<Expression>),
+    Exactly(usize, usize, Box<Expression>),
+    DataSome(usize, Range),
+    DataOnly(usize, Range),
+    DataValue(usize, usize),
+    DataMin(usize, usize, Range),
+    DataMax(usize, usize, Range),
+    DataExactly(usize, usize, Range),
+}
+
+/// A datatype with an optional facet on a literal.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Range {
+    pub datatype: Datatype,
+    pub facet: Option<(RestrictionType, usize)>,
+}
+
+/// The normalized expression of a class description.
+pub fn class(class: &Class) -> Expression {
+    normalize(expression(class))
+}
+
+/// The normalized expression of the class a declaration describes.
+pub fn decl(decl: &ClassDecl) -> Expression {
+    normalize(match decl {
+        ClassDecl::Defined(class, properties) | ClassDecl::Primitive(class, properties) => {
+            intersection(class.index, properties.iter().map(|p| &p.value))
+        }
+        ClassDecl::Enumerated(classes) => {
+            Expression::OneOf(classes.iter().map(|class| class.index).collect())
+        }
+        ClassDecl::Union(classes) => Expression::Or(
+            classes
+                .iter()
+                .map(|class| Expression::Class(class.index))
+                .collect(),
+        ),
+    })
+}
+
+/// The normalized expressions of the class descriptions of a frame.
+pub fn frame(frame: &Frame) -> Vec<Expression> {
+    match frame {
+        Frame::Decl { decl: d, .. } => vec![decl(&d.value)],
+        Frame::Class { super_classes, .. } => super_classes
+            .iter()
+            .map(|super_class| class(&super_class.value))
+            .collect(),
+        Frame::EquivalentClasses(misc) | Frame::DisjointClasses(misc) => {
+            misc.members.iter().map(class).collect()
+        }
+        Frame::AnnotationProperty { .. }
+        | Frame::ObjectProperty { .. }
+        | Frame::DataProperty { .. }
+        | Frame::DisjointProperties(_)
+        | Frame::SameIndividual(_)
+        | Frame::DifferentIndividuals(_) => Vec::new(),
+    }
+}
+
+fn expression(class: &Class) -> Expression {
+    match class {
+        Class::Identifier(class) => Expression::Class(class.index),
+        Class::Defined(class, properties) => {
+            intersection(class.index, properties.iter().map(|p| &p.value))
+        }
+        Class::Enumerated(classes) => {
+            Expression::OneOf(classes.iter().map(|class| class.index).collect())
+        }
+        Class::Union(classes) => Expression::Or(
+            classes
+                .iter()
+                .map(|class| Expression::Class(class.index))
+                .collect(),
+        ),
+        Class::Complement(class) => Expression::Not(Box::new(expression(class))),
+    }
+}
+
+fn intersection<'p>(class: usize, properties: impl Iterator<Item = &'p Property>) -> Expression {
+    let mut operands = vec![Expression::Class(class)];
+    operands.extend(properties.map(restriction));
+    Expression::And(operands)
+}
+
+fn restriction(property: &Property) -> Expression {
+    let p = property.index;
+
+    match &property.description {
+        PropertyDescription::Object(description) => match description {
+            ObjectDescription::Some(class) => Expression::Some(p, Box::new(expression(class))),
+            ObjectDescription::Only(class) => Expression::Only(p, Box::new(expression(class))),
+            ObjectDescription::Value(individual) => Expression::Value(p, individual.index),
+            ObjectDescription::Min(n, class) => Expression::Min(*n, p, Box::new(expression(class))),
+            ObjectDescription::Max(n, class) => Expression::Max(*n, p, Box::new(expression(class))),
+            ObjectDescription::Exactly(n, class) => {
+                Expression::Exactly(*n, p, Box::new(expression(class)))
+            }
+        },
+        PropertyDescription::Data(description) => match description {
+            DataDescription::Some(data) => Expression::DataSome(p, range(data)),
+            DataDescription::Only(data) => Expression::DataOnly(p, range(data)),
+            DataDescription::Value(literal) => Expression::DataValue(p, literal.value.index),
+            DataDescription::Min(n, data) => Expression::DataMin(*n, p, range(data)),
+            DataDescription::Max(n, data) => Expression::DataMax(*n, p, range(data)),
+            DataDescription::Exactly(n, data) => Expression::DataExactly(*n, p, range(data)),
+        },
+    }
+}
+
+fn range(data: &Data) -> Range {
+    Range {
+        datatype: data.datatype,
+        facet: data
+            .restriction
+            .as_ref()
+            .map(|restriction| (restriction.0.clone(), restriction.1.index)),
+    }
+}
+
+/// Puts an expression in negation normal form, flattens nested
+/// intersections and unions, drops repeated operands and sorts the rest.
+pub fn normalize(expression: Expression) -> Expression {
+    simplify(nnf(expression, false))
+}
+
+/// Pushes negation inwards, negating the expression first if `negated`.
+pub fn nnf(expression: Expression, negated: bool) -> Expression {
+    use Expression::*;
+
+    match (expression, negated) {
+        (Not(expression), _) => nnf(*expression, !negated),
+        (Thing, true) => Nothing,
+        (Nothing, true) => Thing,
+        (atom @ (Class(_) | OneOf(_)), true) => Not(Box::new(atom)),
+        (And(operands), true) => Or(operands.into_iter().map(|e| nnf(e, true)).collect()),
+        (Or(operands), true) => And(operands.into_iter().map(|e| nnf(e, true)).collect()),
+        (And(operands), false) => And(operands.into_iter().map(|e| nnf(e, false)).collect()),
+        (Or(operands), false) => Or(operands.into_iter().map(|e| nnf(e, false)).collect()),
+        (Some(p, filler), true) => Only(p, Box::new(nnf(*filler, true))),
+        (Only(p, filler), true) => Some(p, Box::new(nnf(*filler, true))),
+        (Value(p, individual), true) => Only(p, Box::new(Not(Box::new(OneOf(vec![individual]))))),
+        (Min(0, _, _), true) => Nothing,
+        (Min(n, p, filler), true) => Max(n - 1, p, Box::new(nnf(*filler, false))),
+        (Max(n, p, filler), true) => Min(n + 1, p, Box::new(nnf(*filler, false))),
+        (Exactly(n, p, filler), true) => {
+            let filler = nnf(*filler, false);
+            let mut operands = vec![Min(n + 1, p, Box::new(filler.clone()))];
+            if n > 0 {
+                operands.push(Max(n - 1, p, Box::new(filler)));
+            }
+            Or(operands)
+        }
+        (Some(p, filler), false) => Some(p, Box::new(nnf(*filler, false))),
+        (Only(p, filler), false) => Only(p, Box::new(nnf(*filler, false))),
+        (Min(n, p, filler), false) => Min(n, p, Box::new(nnf(*filler, false))),
+        (Max(n, p, filler), false) => Max(n, p, Box::new(nnf(*filler, false))),
+        (Exactly(n, p, filler), false) => Exactly(n, p, Box::new(nnf(*filler, false))),
+        // data ranges have no complement in this syntax, so only the
+        // restrictions with a dual one are rewritten
+        (DataSome(p, range), true) => DataMax(0, p, range),
+        (DataMin(0, _, _), true) => Nothing,
+        (DataMin(n, p, range), true) => DataMax(n - 1, p, range),
+        (DataMax(n, p, range), true) => DataMin(n + 1, p, range),
+        (DataExactly(n, p, range), true) => {
+            let mut operands = vec![DataMin(n + 1, p, range.clone())];
+            if n > 0 {
+                operands.push(DataMax(n - 1, p, range));
+            }
+            Or(operands)
+        }
+        (atom @ (DataOnly(..) | DataValue(..)), true) => Not(Box::new(atom)),
+        (expression, false) => expression,
+    }
+}
+
+fn simplify(expression: Expression) -> Expression {
+    use Expression::*;
+
+    match expression {
+        And(operands) => {
+            let mut flat = Vec::new();
+            flatten(operands, &mut flat, true);
+            if flat.contains(&Nothing) {
+                return Nothing;
+            }
+            flat.retain(|e| *e != Thing);
+            join(flat, And, Thing)
+        }
+        Or(operands) => {
+            let mut flat = Vec::new();
+            flatten(operands, &mut flat, false);
+            if flat.contains(&Thing) {
+                return Thing;
+            }
+            flat.retain(|e| *e != Nothing);
+            join(flat, Or, Nothing)
+        }
+        OneOf(mut individuals) => {
+            individuals.sort();
+            individuals.dedup();
+            OneOf(individuals)
+        }
+        Not(expression) => Not(Box::new(simplify(*expression))),
+        Some(p, filler) => Some(p, Box::new(simplify(*filler))),
+        Only(p, filler) => Only(p, Box::new(simplify(*filler))),
+        Min(n, p, filler) => Min(n, p, Box::new(simplify(*filler))),
+        Max(n, p, filler) => Max(n, p, Box::new(simplify(*filler))),
+        Exactly(n, p, filler) => Exactly(n, p, Box::new(simplify(*filler))),
+        expression => expression,
+    }
+}
+
+// simplified operands of an intersection (`and`) or a union, with the
+// nested ones of the same kind spliced in
+fn flatten(operands: Vec<Expression>, flat: &mut Vec<Expression>, and: bool) {
+    for operand in operands {
+        match (simplify(operand), and) {
+            (Expression::And(nested), true) | (Expression::Or(nested), false) => {
+                flat.extend(nested)
+            }
+            (operand, _) => flat.push(operand),
+        }
+    }
+}
+
+fn join(
+    mut operands: Vec<Expression>,
+    kind: fn(Vec<Expression>) -> Expression,
+    empty: Expression,
+) -> Expression {
+    operands.sort();
+    operands.dedup();
+
+    match operands.len() {
+        0 => empty,
+        1 => operands.remove(0),
+        _ => kind(operands),
+    }
+}
+
+/// Renders an expression in Manchester syntax.
+pub fn render(expression: &Expression, table: &SymbolTable) -> String {
+    use Expression::*;
+
+    let id = |index: usize| table.get(index).map_or("?", |s| s.id()).to_string();
+
+    match expression {
+        Thing => "Thing".to_string(),
+        Nothing => "Nothing".to_string(),
+        Class(class) => id(*class),
+        OneOf(individuals) => format!(
+            "{{{}}}",
+            individuals
+                .iter()
+                .map(|individual| id(*individual))
+                .collect::<Vec<_>>()
+                .join(", ")
+        ),
+        Not(expression) => format!("NOT {}", operand(expression, table)),
+        And(operands) | Or(operands) => operands
+            .iter()
+            .map(|e| operand(e, table))
+            .collect::<Vec<_>>()
+            .join(if matches!(expression, And(_)) {
+                " AND "
+            } else {
+                " OR "
+            }),
+        Some(p, filler) => format!("{} SOME {}", id(*p), operand(filler, table)),
+        Only(p, filler) => format!("{} ONLY {}", id(*p), operand(filler, table)),
+        Value(p, individual) => format!("{} VALUE {}", id(*p), id(*individual)),
+        Min(n, p, filler) => format!("{} MIN {} {}", id(*p), n, operand(filler, table)),
+        Max(n, p, filler) => format!("{} MAX {} {}", id(*p), n, operand(filler, table)),
+        Exactly(n, p, filler) => format!("{} EXACTLY {} {}", id(*p), n, operand(filler, table)),
+        DataSome(p, range) => format!("{} SOME {}", id(*p), render_range(range, table)),
+        DataOnly(p, range) => format!("{} ONLY {}", id(*p), render_range(range, table)),
+        DataValue(p, literal) => format!("{} VALUE \"{}\"", id(*p), id(*literal)),
+        DataMin(n, p, range) => format!("{} MIN {} {}", id(*p), n, render_range(range, table)),
+        DataMax(n, p, range) => format!("{} MAX {} {}", id(*p), n, render_range(range, table)),
+        DataExactly(n, p, range) => {
+            format!("{} EXACTLY {} {}", id(*p), n, render_range(range, table))
+        }
+    }
+}
+
+// an operand of another expression, in parentheses unless it is atomic
+fn operand(expression: &Expression, table: &SymbolTable) -> String {
+    match expression {
+        Expression::Thing
+        | Expression::Nothing
+        | Expression::Class(_)
+        | Expression::OneOf(_)
+        | Expression::Not(_) => render(expression, table),
+        _ => format!("({})", render(expression, table)),
+    }
+}
+
+fn render_range(range: &Range, table: &SymbolTable) -> String {
+    match &range.facet {
+        Some((restriction, literal)) => {
+            let literal = table.get(*literal).map_or("?", |s| s.id());
+            let (datatype, facet) = (range.datatype.name(), restriction.symbol());
+            format!("{}[{}\"{}\"]", datatype, facet, literal)
+        }
+        None => range.datatype.name().to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Expression::*;
+    use super::*;
+
+    const HAS_TOPPING: usize = 0;
+    const CHEESE: usize = 1;
+
+    fn cheese() -> Box<Expression> {
+        Box::new(Class(CHEESE))
+    }
+
+    #[test]
+    fn negated_exactly_is_fewer_or_more() {
+        let exactly = Exactly(2, HAS_TOPPING, cheese());
+
+        assert_eq!(
+            nnf(exactly.clone(), true),
+            Or(vec![
+                Min(3, HAS_TOPPING, cheese()),
+                Max(1, HAS_TOPPING, cheese())
+            ])
+        );
+        assert_eq!(
+            normalize(Not(Box::new(exactly.clone()))),
+            nnf(exactly, true)
+        );
+    }
+
+    #[test]
+    fn negated_exactly_zero_is_at_least_one() {
+        assert_eq!(
+            normalize(Not(Box::new(Exactly(0, HAS_TOPPING, cheese())))),
+            Min(1, HAS_TOPPING, cheese())
+        );
+    }
+
+    #[test]
+    fn exactly_keeps_its_filler_in_normal_form() {
+        let filler = Not(Box::new(Not(Box::new(Class(CHEESE)))));
+
+        assert_eq!(
+            nnf(Exactly(1, HAS_TOPPING, Box::new(filler.clone())), false),
+            Exactly(1, HAS_TOPPING, cheese())
+        );
+        assert_eq!(
+            nnf(Exactly(1, HAS_TOPPING, Box::new(filler)), true),
+            Or(vec![
+                Min(2, HAS_TOPPING, cheese()),
+                Max(0, HAS_TOPPING, cheese())
+            ])
+        );
+    }
+
+    #[test]
+    fn negated_data_exactly_is_fewer_or_more() {
+        let range = Range {
+            datatype: Datatype::Integer,
+            facet: None,
+        };
+
+        assert_eq!(
+            normalize(Not(Box::new(DataExactly(1, HAS_TOPPING, range.clone())))),
+            Or(vec![
+                DataMin(2, HAS_TOPPING, range.clone()),
+                DataMax(0, HAS_TOPPING, range)
+            ])
+        );
+    }
+}
@@ -1,35 +1,97 @@
 use std::{fmt::Debug, iter::Peekable, slice::Iter, vec};
 
 use crate::{
-    lexer::{Datatype, Token},
-    span::{Located, Span},
+    lexer::{Datatype, Section, Token},
+    span::{Located, SourceMap, Span},
     table::{PropertyType, SymbolTable, Type},
 };
 
+pub struct Ontology {
+    pub iri: Option<Located<String>>,
+    pub imports: Vec<Located<String>>,
+    pub frames: Vec<Located<Frame>>,
+}
+
+pub enum Frame {
+    Class {
+        class: ClassIdentifier,
+        annotations: Vec<Annotation>,
+        super_classes: Vec<Located<Class>>,
+    },
+    AnnotationProperty {
+        property: AnnotationPropertyIdentifier,
+        annotations: Vec<Annotation>,
+    },
+    Decl {
+        annotations: Vec<Annotation>,
+        decl: Located<ClassDecl>,
+    },
+    ObjectProperty {
+        property: PropertyIdentifier,
+        annotations: Vec<Annotation>,
+        chains: Vec<PropertyChain>,
+    },
+    DataProperty {
+        property: PropertyIdentifier,
+        annotations: Vec<Annotation>,
+        ranges: Vec<Located<Data>>,
+    },
+    DisjointClasses(Misc<Class>),
+    EquivalentClasses(Misc<Class>),
+    DisjointProperties(Misc<PropertyIdentifier>),
+    SameIndividual(Misc<IndividualIdentifier>),
+    DifferentIndividuals(Misc<IndividualIdentifier>),
+}
+
+/// Members of a misc frame such as `DisjointClasses: A, B, C`.
+pub struct Misc<T> {
+    pub annotations: Vec<Annotation>,
+    pub members: Vec<T>,
+}
+
 pub enum ClassDecl {
-    Defined(ClassIdentifier, Vec<Property>),
-    Primitive(ClassIdentifier, Vec<Property>),
+    Defined(ClassIdentifier, Vec<Located<Property>>),
+    Primitive(ClassIdentifier, Vec<Located<Property>>),
     Enumerated(Vec<ClassIdentifier>),
-    Disjoint(Vec<ClassIdentifier>),
+    /// `A OR B OR ...` at the top level, a covering axiom rather than a
+    /// disjointness one.
+    Union(Vec<ClassIdentifier>),
 }
 
-#[derive(PartialEq, Eq)]
 pub enum Class {
     Identifier(ClassIdentifier),
-    Defined(ClassIdentifier, Vec<Property>),
+    Defined(ClassIdentifier, Vec<Located<Property>>),
     Enumerated(Vec<ClassIdentifier>),
-    Disjoint(Vec<ClassIdentifier>),
+    Union(Vec<ClassIdentifier>),
+    /// `NOT C`
+    Complement(Box<Class>),
 }
 
 #[derive(PartialEq, Eq)]
 pub struct ClassIdentifier {
-    index: usize,
+    pub index: usize,
+}
+
+/// A name used as an individual, lexed like a class and recorded as
+/// `Type::Individual` in the symbol table.
+#[derive(PartialEq, Eq)]
+pub struct IndividualIdentifier {
+    pub index: usize,
 }
 
+#[derive(PartialEq, Eq)]
+pub struct PropertyIdentifier {
+    pub index: usize,
+}
+
+/// `p1 o p2 o ...`, the object properties composed by a `SubPropertyChain:`.
+#[derive(Debug, PartialEq, Eq)]
+pub struct PropertyChain(pub Vec<PropertyIdentifier>);
+
 #[derive(Debug, PartialEq, Eq)]
 pub struct Property {
-    index: usize,
-    description: PropertyDescription,
+    pub index: usize,
+    pub description: PropertyDescription,
 }
 
 #[derive(PartialEq, Eq)]
@@ -48,11 +110,10 @@ pub enum ObjectDescription {
     Exactly(usize, Class),
 }
 
-#[derive(Debug, PartialEq, Eq)]
 pub enum DataDescription {
     Some(Data),
     Only(Data),
-    Value(Literal),
+    Value(Located<Literal>),
     Min(usize, Data),
     Max(usize, Data),
     Exactly(usize, Data),
@@ -60,16 +121,33 @@ pub enum DataDescription {
 
 #[derive(PartialEq, Eq)]
 pub struct Literal {
-    index: usize,
+    pub index: usize,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct Annotation {
+    pub property: AnnotationPropertyIdentifier,
+    pub value: AnnotationValue,
 }
 
 #[derive(PartialEq, Eq)]
-pub struct Data {
-    datatype: Datatype,
-    restriction: Option<Restriction>,
+pub struct AnnotationPropertyIdentifier {
+    pub index: usize,
 }
 
 #[derive(Debug, PartialEq, Eq)]
+pub enum AnnotationValue {
+    Literal(Literal, Option<String>),
+    Iri(String),
+}
+
+#[derive(PartialEq, Eq)]
+pub struct Data {
+    pub datatype: Datatype,
+    pub restriction: Option<Restriction>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub enum RestrictionType {
     LessThanEqual,
     GreaterThanEqual,
@@ -78,44 +156,423 @@ pub enum RestrictionType {
 }
 
 #[derive(Debug, PartialEq, Eq)]
-pub struct Restriction(RestrictionType, Literal);
+pub struct Restriction(pub RestrictionType, pub Literal);
 
-pub enum ParserErr<'t> {
+pub enum ParserErr {
     UnexpectedEndOfInput,
-    RepeatedProperty(Located<'t, Property>),
-    RepeatedClass(Located<'t, ClassIdentifier>),
+    RepeatedClass(Located<ClassIdentifier>),
     UnrecognizedToken {
         expected: &'static str,
-        found: &'t Located<'t, Token>,
+        found: Located<Token>,
     },
     TypeMismatch {
-        location: Span<'t>,
+        location: Span,
         expected: Type,
         found: Type,
+        previous: Option<Span>,
+    },
+    IllTypedLiteral {
+        literal: Located<Literal>,
+        datatype: Datatype,
     },
 }
 
 use crate::lexer::Keyword::*;
 use crate::lexer::Punctuation::*;
 
-type Tokens<'t> = Peekable<Iter<'t, Located<'t, Token>>>;
+type Tokens<'t> = Peekable<Iter<'t, Located<Token>>>;
 
-type ParserResult<'t, T> = Result<(Tokens<'t>, Located<'t, T>), ParserErr<'t>>;
+type ParserResult<'t, T> = Result<(Tokens<'t>, Located<T>), ParserErr>;
 
-pub fn parse<'t>(
-    tokens: &'t [Located<'t, Token>],
-    table: &mut SymbolTable,
-) -> Result<Vec<Located<'t, ClassDecl>>, ParserErr<'t>> {
+pub fn parse(tokens: &[Located<Token>], table: &mut SymbolTable) -> Result<Ontology, ParserErr> {
     let mut tokens = tokens.iter().peekable();
-    let mut decls = Vec::new();
+    let mut iri = None;
+    let mut imports = Vec::new();
+    let mut frames = Vec::new();
 
-    while let Some(_) = tokens.peek() {
-        let (remaining, decl) = parse_decl(tokens.clone(), table)?;
+    if let Some(Token::Section(Section::Ontology)) = tokens.peek().map(|t| &t.value) {
+        tokens.next();
+        let (remaining, ontology_iri) = parse_iri(tokens)?;
         tokens = remaining;
-        decls.push(decl);
+        iri = Some(ontology_iri);
     }
 
-    Ok(decls)
+    while let Some(Token::Section(Section::Import)) = tokens.peek().map(|t| &t.value) {
+        tokens.next();
+        let (remaining, import) = parse_iri(tokens)?;
+        tokens = remaining;
+        imports.push(import);
+    }
+
+    while tokens.peek().is_some() {
+        let (remaining, frame) = parse_frame(tokens.clone(), table)?;
+        tokens = remaining;
+        frames.push(frame);
+    }
+
+    Ok(Ontology {
+        iri,
+        imports,
+        frames,
+    })
+}
+
+fn parse_frame<'t>(mut tokens: Tokens<'t>, table: &mut SymbolTable) -> ParserResult<'t, Frame> {
+    match tokens.peek().cloned() {
+        Some(token) => match token.value {
+            Token::Section(Section::Class) => {
+                tokens.next();
+                let (remaining, class) = parse_class_identifier(tokens)?;
+                tokens = remaining;
+                let mut span = token.span.merge(&class.span);
+                let mut annotations = Vec::new();
+                let mut super_classes = Vec::new();
+
+                loop {
+                    match tokens.peek().map(|t| &t.value) {
+                        Some(Token::Section(Section::Annotations)) if annotations.is_empty() => {
+                            let (remaining, located) = parse_annotations(tokens)?;
+                            tokens = remaining;
+                            span = span.merge(&located.span);
+                            annotations = located.value;
+                        }
+                        Some(Token::Section(Section::SubClassOf)) => {
+                            tokens.next();
+                            let (remaining, super_class) = parse_class(tokens, table)?;
+                            tokens = remaining;
+                            span = span.merge(&super_class.span);
+                            super_classes.push(super_class);
+
+                            while let Some(Token::Punctuation(Comma)) =
+                                tokens.peek().map(|t| &t.value)
+                            {
+                                tokens.next();
+                                let (remaining, super_class) = parse_class(tokens, table)?;
+                                tokens = remaining;
+                                span = span.merge(&super_class.span);
+                                super_classes.push(super_class);
+                            }
+                        }
+                        _ => break,
+                    }
+                }
+
+                for annotation in &annotations {
+                    table.annotate(class.value.index, annotation.describe(table));
+                }
+
+                let class = class.value;
+                let frame = Frame::Class {
+                    class,
+                    annotations,
+                    super_classes,
+                };
+                Ok((tokens, Located::new(frame, span)))
+            }
+            Token::Section(Section::AnnotationProperty) => {
+                tokens.next();
+                let (remaining, property) = parse_annotation_property(tokens)?;
+                tokens = remaining;
+                let mut span = token.span.merge(&property.span);
+                let mut annotations = Vec::new();
+
+                if let Some(Token::Section(Section::Annotations)) = tokens.peek().map(|t| &t.value)
+                {
+                    let (remaining, located) = parse_annotations(tokens)?;
+                    tokens = remaining;
+                    span = span.merge(&located.span);
+                    annotations = located.value;
+                }
+
+                for annotation in &annotations {
+                    table.annotate(property.value.index, annotation.describe(table));
+                }
+
+                let property = property.value;
+                let frame = Frame::AnnotationProperty {
+                    property,
+                    annotations,
+                };
+                Ok((tokens, Located::new(frame, span)))
+            }
+            Token::Section(Section::ObjectProperty) => {
+                tokens.next();
+                let (remaining, property) = parse_property_identifier(tokens)?;
+                tokens = remaining;
+                let mut span = token.span.merge(&property.span);
+                assert_property_type(&property, PropertyType::Object, table)?;
+                let mut annotations = Vec::new();
+                let mut chains = Vec::new();
+
+                loop {
+                    match tokens.peek().map(|t| &t.value) {
+                        Some(Token::Section(Section::Annotations)) if annotations.is_empty() => {
+                            let (remaining, located) = parse_annotations(tokens)?;
+                            tokens = remaining;
+                            span = span.merge(&located.span);
+                            annotations = located.value;
+                        }
+                        Some(Token::Section(Section::SubPropertyChain)) => {
+                            tokens.next();
+                            let (remaining, chain) = parse_property_chain(tokens, table)?;
+                            tokens = remaining;
+                            span = span.merge(&chain.span);
+                            chains.push(chain.value);
+                        }
+                        _ => break,
+                    }
+                }
+
+                for annotation in &annotations {
+                    table.annotate(property.value.index, annotation.describe(table));
+                }
+
+                let property = property.value;
+                let frame = Frame::ObjectProperty {
+                    property,
+                    annotations,
+                    chains,
+                };
+                Ok((tokens, Located::new(frame, span)))
+            }
+            Token::Section(Section::DataProperty) => {
+                tokens.next();
+                let (remaining, property) = parse_property_identifier(tokens)?;
+                tokens = remaining;
+                let mut span = token.span.merge(&property.span);
+                assert_property_type(&property, PropertyType::Data, table)?;
+                let mut annotations = Vec::new();
+                let mut ranges = Vec::new();
+
+                loop {
+                    match tokens.peek().map(|t| &t.value) {
+                        Some(Token::Section(Section::Annotations)) if annotations.is_empty() => {
+                            let (remaining, located) = parse_annotations(tokens)?;
+                            tokens = remaining;
+                            span = span.merge(&located.span);
+                            annotations = located.value;
+                        }
+                        Some(Token::Section(Section::Range)) => {
+                            tokens.next();
+                            let (remaining, data) = parse_data(tokens, table)?;
+                            tokens = remaining;
+                            span = span.merge(&data.span);
+                            ranges.push(data);
+                        }
+                        _ => break,
+                    }
+                }
+
+                for annotation in &annotations {
+                    table.annotate(property.value.index, annotation.describe(table));
+                }
+
+                let property = property.value;
+                let frame = Frame::DataProperty {
+                    property,
+                    annotations,
+                    ranges,
+                };
+                Ok((tokens, Located::new(frame, span)))
+            }
+            Token::Section(Section::DisjointClasses) => {
+                tokens.next();
+                let (tokens, misc) = parse_misc(tokens, |t| parse_class(t, table))?;
+                let span = token.span.merge(&misc.span);
+                let frame = Frame::DisjointClasses(misc.value);
+                Ok((tokens, Located::new(frame, span)))
+            }
+            Token::Section(Section::EquivalentClasses) => {
+                tokens.next();
+                let (tokens, misc) = parse_misc(tokens, |t| parse_class(t, table))?;
+                let span = token.span.merge(&misc.span);
+                let frame = Frame::EquivalentClasses(misc.value);
+                Ok((tokens, Located::new(frame, span)))
+            }
+            Token::Section(Section::DisjointProperties) => {
+                tokens.next();
+                let (tokens, misc) = parse_misc(tokens, parse_property_identifier)?;
+                let span = token.span.merge(&misc.span);
+                assert_same_property_type(&misc.value.members, misc.span, table)?;
+                let frame = Frame::DisjointProperties(misc.value);
+                Ok((tokens, Located::new(frame, span)))
+            }
+            Token::Section(Section::SameIndividual) => {
+                tokens.next();
+                let (tokens, misc) = parse_misc(tokens, |t| parse_individual(t, table))?;
+                let span = token.span.merge(&misc.span);
+                let frame = Frame::SameIndividual(misc.value);
+                Ok((tokens, Located::new(frame, span)))
+            }
+            Token::Section(Section::DifferentIndividuals) => {
+                tokens.next();
+                let (tokens, misc) = parse_misc(tokens, |t| parse_individual(t, table))?;
+                let span = token.span.merge(&misc.span);
+                let frame = Frame::DifferentIndividuals(misc.value);
+                Ok((tokens, Located::new(frame, span)))
+            }
+            Token::Section(Section::Annotations) => {
+                let (remaining, annotations) = parse_annotations(tokens)?;
+                let (tokens, decl) = parse_decl(remaining, table)?;
+                let span = annotations.span.merge(&decl.span);
+                let annotations = annotations.value;
+                let frame = Frame::Decl { annotations, decl };
+                Ok((tokens, Located::new(frame, span)))
+            }
+            _ => {
+                let (tokens, decl) = parse_decl(tokens, table)?;
+                let span = decl.span;
+                let annotations = Vec::new();
+                let frame = Frame::Decl { annotations, decl };
+                Ok((tokens, Located::new(frame, span)))
+            }
+        },
+        None => Err(ParserErr::UnexpectedEndOfInput),
+    }
+}
+
+fn parse_misc<'t, T: PartialEq, F: FnMut(Tokens<'t>) -> ParserResult<'t, T>>(
+    mut tokens: Tokens<'t>,
+    mut parse_member: F,
+) -> ParserResult<'t, Misc<T>> {
+    let mut annotations = Vec::new();
+    let mut span = None;
+
+    if let Some(Token::Section(Section::Annotations)) = tokens.peek().map(|t| &t.value) {
+        let (remaining, located) = parse_annotations(tokens)?;
+        tokens = remaining;
+        span = Some(located.span);
+        annotations = located.value;
+    }
+
+    let (remaining, member) = parse_member(tokens)?;
+    tokens = remaining;
+    let mut span = span.map_or(member.span, |span| span.merge(&member.span));
+    let mut members = vec![member.value];
+
+    // misc frames relate at least two members
+    loop {
+        match tokens.peek().cloned() {
+            Some(token) if matches!(token.value, Token::Punctuation(Comma)) => {
+                tokens.next();
+                let (remaining, member) = parse_member(tokens)?;
+                tokens = remaining;
+                span = span.merge(&member.span);
+                members.push(member.value);
+            }
+            Some(token) if members.len() < 2 => {
+                return Err(ParserErr::UnrecognizedToken {
+                    expected: "','",
+                    found: token.clone(),
+                })
+            }
+            None if members.len() < 2 => return Err(ParserErr::UnexpectedEndOfInput),
+            _ => break,
+        }
+    }
+
+    let misc = Misc {
+        annotations,
+        members,
+    };
+    Ok((tokens, Located::new(misc, span)))
+}
+
+fn parse_annotations<'t>(mut tokens: Tokens<'t>) -> ParserResult<'t, Vec<Annotation>> {
+    match tokens.next() {
+        Some(token) => match token.value {
+            Token::Section(Section::Annotations) => {
+                let (remaining, annotation) = parse_annotation(tokens)?;
+                tokens = remaining;
+                let mut span = token.span.merge(&annotation.span);
+                let mut annotations = vec![annotation.value];
+
+                while let Some(Token::Punctuation(Comma)) = tokens.peek().map(|t| &t.value) {
+                    tokens.next();
+                    let (remaining, annotation) = parse_annotation(tokens)?;
+                    tokens = remaining;
+                    span = span.merge(&annotation.span);
+                    annotations.push(annotation.value);
+                }
+
+                Ok((tokens, Located::new(annotations, span)))
+            }
+            _ => Err(ParserErr::UnrecognizedToken {
+                expected: "'Annotations:'",
+                found: token.clone(),
+            }),
+        },
+        None => Err(ParserErr::UnexpectedEndOfInput),
+    }
+}
+
+fn parse_annotation<'t>(tokens: Tokens<'t>) -> ParserResult<'t, Annotation> {
+    let (mut tokens, property) = parse_annotation_property(tokens)?;
+
+    match tokens.next() {
+        Some(token) => match &token.value {
+            Token::Literal { index } => {
+                let literal = Literal { index: *index };
+                let mut span = property.span.merge(&token.span);
+                let mut language = None;
+
+                if let Some(Token::LanguageTag(tag)) = tokens.peek().map(|t| &t.value) {
+                    language = Some(tag.clone());
+                    span = span.merge(&tokens.next().unwrap().span);
+                }
+
+                let value = AnnotationValue::Literal(literal, language);
+                let property = property.value;
+                Ok((tokens, Located::new(Annotation { property, value }, span)))
+            }
+            Token::Iri(iri) => {
+                let span = property.span.merge(&token.span);
+                let value = AnnotationValue::Iri(iri.clone());
+                let property = property.value;
+                Ok((tokens, Located::new(Annotation { property, value }, span)))
+            }
+            _ => Err(ParserErr::UnrecognizedToken {
+                expected: "Literal or IRI",
+                found: token.clone(),
+            }),
+        },
+        None => Err(ParserErr::UnexpectedEndOfInput),
+    }
+}
+
+fn parse_annotation_property<'t>(
+    mut tokens: Tokens<'t>,
+) -> ParserResult<'t, AnnotationPropertyIdentifier> {
+    match tokens.next() {
+        Some(token) => match token.value {
+            Token::AnnotationPropertyIdentifier { index } => {
+                let property = AnnotationPropertyIdentifier { index };
+                let span = token.span;
+                Ok((tokens, Located::new(property, span)))
+            }
+            _ => Err(ParserErr::UnrecognizedToken {
+                expected: "AnnotationPropertyIdentifier",
+                found: token.clone(),
+            }),
+        },
+        None => Err(ParserErr::UnexpectedEndOfInput),
+    }
+}
+
+fn parse_iri<'t>(mut tokens: Tokens<'t>) -> ParserResult<'t, String> {
+    match tokens.next() {
+        Some(token) => match &token.value {
+            Token::Iri(iri) => {
+                let iri = Located::new(iri.clone(), token.span);
+                Ok((tokens, iri))
+            }
+            _ => Err(ParserErr::UnrecognizedToken {
+                expected: "IRI",
+                found: token.clone(),
+            }),
+        },
+        None => Err(ParserErr::UnexpectedEndOfInput),
+    }
 }
 
 fn parse_decl<'t>(mut tokens: Tokens<'t>, table: &mut SymbolTable) -> ParserResult<'t, ClassDecl> {
@@ -126,7 +583,7 @@ fn parse_decl<'t>(mut tokens: Tokens<'t>, table: &mut SymbolTable) -> ParserResu
                 let class_identifier = ClassIdentifier { index };
                 let span = token.span;
 
-                match tokens.peek() {
+                match tokens.peek().cloned() {
                     Some(token) => match token.value {
                         Token::Keyword(AND) => {
                             tokens.next();
@@ -134,7 +591,7 @@ fn parse_decl<'t>(mut tokens: Tokens<'t>, table: &mut SymbolTable) -> ParserResu
                             tokens = remaining;
                             let mut span = span.merge(&property.span);
                             assert_property(&property, table)?;
-                            let mut properties = vec![property.value];
+                            let mut properties = vec![property];
 
                             while let Some(Token::Keyword(AND)) = tokens.peek().map(|t| &t.value) {
                                 tokens.next();
@@ -142,11 +599,8 @@ fn parse_decl<'t>(mut tokens: Tokens<'t>, table: &mut SymbolTable) -> ParserResu
                                 tokens = remaining;
                                 span = span.merge(&property.span);
                                 assert_property(&property, table)?;
-                                if properties.iter().any(|p| *p == property.value) {
-                                    return Err(ParserErr::RepeatedProperty(property));
-                                }
 
-                                properties.push(property.value);
+                                properties.push(property);
                             }
 
                             let class_decl = ClassDecl::Defined(class_identifier, properties);
@@ -167,13 +621,13 @@ fn parse_decl<'t>(mut tokens: Tokens<'t>, table: &mut SymbolTable) -> ParserResu
                                 let (remaining, class) = parse_class_identifier(tokens)?;
                                 tokens = remaining;
                                 span = span.merge(&class.span);
-                                if classes.iter().any(|c| *c == class.value) {
+                                if classes.contains(&class.value) {
                                     return Err(ParserErr::RepeatedClass(class));
                                 }
                                 classes.push(class.value);
                             }
 
-                            let class = ClassDecl::Disjoint(classes);
+                            let class = ClassDecl::Union(classes);
                             Ok((tokens, Located::new(class, span)))
                         }
                         Token::PropertyIdentifier { .. } => {
@@ -181,7 +635,7 @@ fn parse_decl<'t>(mut tokens: Tokens<'t>, table: &mut SymbolTable) -> ParserResu
                             tokens = remaining;
                             let mut span = span.merge(&property.span);
                             assert_property(&property, table)?;
-                            let mut properties = vec![property.value];
+                            let mut properties = vec![property];
 
                             while let Some(Token::PropertyIdentifier { .. }) =
                                 tokens.peek().map(|t| &t.value)
@@ -190,11 +644,8 @@ fn parse_decl<'t>(mut tokens: Tokens<'t>, table: &mut SymbolTable) -> ParserResu
                                 tokens = remaining;
                                 span = span.merge(&property.span);
                                 assert_property(&property, table)?;
-                                if properties.iter().any(|p| *p == property.value) {
-                                    return Err(ParserErr::RepeatedProperty(property));
-                                }
 
-                                properties.push(property.value);
+                                properties.push(property);
                             }
 
                             let class_decl = ClassDecl::Primitive(class_identifier, properties);
@@ -202,17 +653,17 @@ fn parse_decl<'t>(mut tokens: Tokens<'t>, table: &mut SymbolTable) -> ParserResu
                         }
                         _ => Err(ParserErr::UnrecognizedToken {
                             expected: "'AND' or 'OR' or PropertyIdentifier",
-                            found: token,
+                            found: token.clone(),
                         }),
                     },
                     None => Err(ParserErr::UnexpectedEndOfInput),
                 }
             }
             Token::Punctuation(OpenBrace) => parse_enumerated_class(tokens)
-                .map(|(tokens, classes)| (tokens, classes.map(|cs| ClassDecl::Enumerated(cs)))),
+                .map(|(tokens, classes)| (tokens, classes.map(ClassDecl::Enumerated))),
             _ => Err(ParserErr::UnrecognizedToken {
                 expected: "ClassIdentifier or '{'",
-                found: token,
+                found: token.clone(),
             }),
         },
         None => Err(ParserErr::UnexpectedEndOfInput),
@@ -237,7 +688,7 @@ fn parse_property<'t>(
                         }
                         _ => Err(ParserErr::UnrecognizedToken {
                             expected: "')'",
-                            found: token,
+                            found: token.clone(),
                         }),
                     },
                     None => Err(ParserErr::UnexpectedEndOfInput),
@@ -257,7 +708,7 @@ fn parse_property<'t>(
                                 Ok((tokens, Located::new(property, span)))
                             }
                             Err(ParserErr::UnrecognizedToken { .. }) => {
-                                match parse_data(tokens.clone()) {
+                                match parse_data(tokens.clone(), table) {
                                     Ok((tokens, data)) => {
                                         span = span.merge(&data.span);
                                         let description = PropertyDescription::Data(
@@ -281,7 +732,7 @@ fn parse_property<'t>(
                                 Ok((tokens, Located::new(property, span)))
                             }
                             Err(ParserErr::UnrecognizedToken { .. }) => {
-                                match parse_data(tokens.clone()) {
+                                match parse_data(tokens.clone(), table) {
                                     Ok((tokens, data)) => {
                                         span = span.merge(&data.span);
                                         let description = PropertyDescription::Data(
@@ -307,10 +758,11 @@ fn parse_property<'t>(
                             Err(ParserErr::UnrecognizedToken { .. }) => {
                                 match parse_literal(tokens.clone()) {
                                     Ok((tokens, data)) => {
+                                        // the datatype of the property is only known once the
+                                        // whole ontology is parsed, see `checks`
                                         span = span.merge(&data.span);
-                                        let description = PropertyDescription::Data(
-                                            DataDescription::Value(data.value),
-                                        );
+                                        let description =
+                                            PropertyDescription::Data(DataDescription::Value(data));
                                         let property = Property { index, description };
                                         Ok((tokens, Located::new(property, span)))
                                     }
@@ -320,7 +772,8 @@ fn parse_property<'t>(
                             Err(err) => Err(err),
                         },
                         Token::Keyword(MIN) => {
-                            let Some(Token::Cardinality(min)) = tokens.next().map(|t| &t.value) else {
+                            let Some(Token::Cardinality(min)) = tokens.next().map(|t| &t.value)
+                            else {
                                 return Err(ParserErr::UnexpectedEndOfInput);
                             };
 
@@ -336,7 +789,7 @@ fn parse_property<'t>(
                                     Ok((tokens, Located::new(property, span)))
                                 }
                                 Err(ParserErr::UnrecognizedToken { .. }) => {
-                                    match parse_data(tokens.clone()) {
+                                    match parse_data(tokens.clone(), table) {
                                         Ok((tokens, data)) => {
                                             span = span.merge(&data.span);
                                             let description = PropertyDescription::Data(
@@ -352,7 +805,8 @@ fn parse_property<'t>(
                             }
                         }
                         Token::Keyword(MAX) => {
-                            let Some(Token::Cardinality(max)) = tokens.next().map(|t| &t.value) else {
+                            let Some(Token::Cardinality(max)) = tokens.next().map(|t| &t.value)
+                            else {
                                 return Err(ParserErr::UnexpectedEndOfInput);
                             };
 
@@ -368,7 +822,7 @@ fn parse_property<'t>(
                                     Ok((tokens, Located::new(property, span)))
                                 }
                                 Err(ParserErr::UnrecognizedToken { .. }) => {
-                                    match parse_data(tokens.clone()) {
+                                    match parse_data(tokens.clone(), table) {
                                         Ok((tokens, data)) => {
                                             span = span.merge(&data.span);
                                             let description = PropertyDescription::Data(
@@ -384,7 +838,8 @@ fn parse_property<'t>(
                             }
                         }
                         Token::Keyword(EXACTLY) => {
-                            let Some(Token::Cardinality(exactly)) = tokens.next().map(|t| &t.value) else {
+                            let Some(Token::Cardinality(exactly)) = tokens.next().map(|t| &t.value)
+                            else {
                                 return Err(ParserErr::UnexpectedEndOfInput);
                             };
 
@@ -400,7 +855,7 @@ fn parse_property<'t>(
                                     Ok((tokens, Located::new(property, span)))
                                 }
                                 Err(ParserErr::UnrecognizedToken { .. }) => {
-                                    match parse_data(tokens.clone()) {
+                                    match parse_data(tokens.clone(), table) {
                                         Ok((tokens, data)) => {
                                             span = span.merge(&data.span);
                                             let description = PropertyDescription::Data(
@@ -417,7 +872,7 @@ fn parse_property<'t>(
                         }
                         _ => Err(ParserErr::UnrecognizedToken {
                             expected: "'SOME' or 'ONLY' or 'VALUE' or 'MIN' or 'MAX' or 'EXACTLY'",
-                            found: token,
+                            found: token.clone(),
                         }),
                     },
                     None => Err(ParserErr::UnexpectedEndOfInput),
@@ -425,7 +880,7 @@ fn parse_property<'t>(
             }
             _ => Err(ParserErr::UnrecognizedToken {
                 expected: "'(' or PropertyIdentifier",
-                found: token,
+                found: token.clone(),
             }),
         },
         None => Err(ParserErr::UnexpectedEndOfInput),
@@ -446,7 +901,7 @@ fn parse_enumerated_class<'t>(mut tokens: Tokens<'t>) -> ParserResult<'t, Vec<Cl
                     let (remaining, class) = parse_class_identifier(tokens)?;
                     tokens = remaining;
                     span = span.merge(&class.span);
-                    if classes.iter().any(|c| *c == class.value) {
+                    if classes.contains(&class.value) {
                         return Err(ParserErr::RepeatedClass(class));
                     }
                     classes.push(class.value);
@@ -460,7 +915,7 @@ fn parse_enumerated_class<'t>(mut tokens: Tokens<'t>) -> ParserResult<'t, Vec<Cl
                         }
                         _ => Err(ParserErr::UnrecognizedToken {
                             expected: "'}'",
-                            found: token,
+                            found: token.clone(),
                         }),
                     },
                     None => Err(ParserErr::UnexpectedEndOfInput),
@@ -468,7 +923,7 @@ fn parse_enumerated_class<'t>(mut tokens: Tokens<'t>) -> ParserResult<'t, Vec<Cl
             }
             _ => Err(ParserErr::UnrecognizedToken {
                 expected: "'{'",
-                found: token,
+                found: token.clone(),
             }),
         },
         None => Err(ParserErr::UnexpectedEndOfInput),
@@ -485,13 +940,77 @@ fn parse_class_identifier<'t>(mut tokens: Tokens<'t>) -> ParserResult<'t, ClassI
             }
             _ => Err(ParserErr::UnrecognizedToken {
                 expected: "ClassIdentifier",
-                found: token,
+                found: token.clone(),
             }),
         },
         None => Err(ParserErr::UnexpectedEndOfInput),
     }
 }
 
+// a class identifier used as an individual, recorded as one in the table
+fn parse_individual<'t>(
+    tokens: Tokens<'t>,
+    table: &mut SymbolTable,
+) -> ParserResult<'t, IndividualIdentifier> {
+    let (tokens, class) = parse_class_identifier(tokens)?;
+    table.update_individual(class.value.index, class.span);
+    let individual = IndividualIdentifier {
+        index: class.value.index,
+    };
+    Ok((tokens, Located::new(individual, class.span)))
+}
+
+fn parse_property_identifier<'t>(mut tokens: Tokens<'t>) -> ParserResult<'t, PropertyIdentifier> {
+    match tokens.next() {
+        Some(token) => match token.value {
+            Token::PropertyIdentifier { index } => {
+                let property = PropertyIdentifier { index };
+                let span = token.span;
+                Ok((tokens, Located::new(property, span)))
+            }
+            _ => Err(ParserErr::UnrecognizedToken {
+                expected: "PropertyIdentifier",
+                found: token.clone(),
+            }),
+        },
+        None => Err(ParserErr::UnexpectedEndOfInput),
+    }
+}
+
+fn parse_property_chain<'t>(
+    tokens: Tokens<'t>,
+    table: &mut SymbolTable,
+) -> ParserResult<'t, PropertyChain> {
+    let (mut tokens, property) = parse_property_identifier(tokens)?;
+    let mut span = property.span;
+    assert_property_type(&property, PropertyType::Object, table)?;
+    let mut properties = vec![property.value];
+
+    // a chain composes at least two properties
+    loop {
+        match tokens.peek().cloned() {
+            Some(token) if matches!(token.value, Token::Keyword(O)) => {
+                tokens.next();
+                let (remaining, property) = parse_property_identifier(tokens)?;
+                tokens = remaining;
+                span = span.merge(&property.span);
+                assert_property_type(&property, PropertyType::Object, table)?;
+                properties.push(property.value);
+            }
+            Some(token) if properties.len() < 2 => {
+                return Err(ParserErr::UnrecognizedToken {
+                    expected: "'o'",
+                    found: token.clone(),
+                })
+            }
+            None if properties.len() < 2 => return Err(ParserErr::UnexpectedEndOfInput),
+            _ => break,
+        }
+    }
+
+    Ok((tokens, Located::new(PropertyChain(properties), span)))
+}
+
 fn parse_class<'t>(mut tokens: Tokens<'t>, table: &mut SymbolTable) -> ParserResult<'t, Class> {
     match tokens.peek().cloned() {
         Some(token) => match token.value {
@@ -510,7 +1029,7 @@ fn parse_class<'t>(mut tokens: Tokens<'t>, table: &mut SymbolTable) -> ParserRes
 
                             assert_property(&property, table)?;
 
-                            let mut properties = vec![property.value];
+                            let mut properties = vec![property];
 
                             while let Some(Token::Keyword(AND)) = tokens.peek().map(|t| &t.value) {
                                 tokens.next();
@@ -518,11 +1037,8 @@ fn parse_class<'t>(mut tokens: Tokens<'t>, table: &mut SymbolTable) -> ParserRes
                                 tokens = remaining;
                                 span = span.merge(&property.span);
                                 assert_property(&property, table)?;
-                                if properties.iter().any(|p| *p == property.value) {
-                                    return Err(ParserErr::RepeatedProperty(property));
-                                }
 
-                                properties.push(property.value);
+                                properties.push(property);
                             }
 
                             let class = Class::Defined(class_identifier, properties);
@@ -543,13 +1059,13 @@ fn parse_class<'t>(mut tokens: Tokens<'t>, table: &mut SymbolTable) -> ParserRes
                                 let (remaining, class) = parse_class_identifier(tokens)?;
                                 tokens = remaining;
                                 span = span.merge(&class.span);
-                                if classes.iter().any(|c| *c == class.value) {
+                                if classes.contains(&class.value) {
                                     return Err(ParserErr::RepeatedClass(class));
                                 }
                                 classes.push(class.value);
                             }
 
-                            let class = Class::Disjoint(classes);
+                            let class = Class::Union(classes);
                             Ok((tokens, Located::new(class, span)))
                         }
                         _ => {
@@ -557,7 +1073,10 @@ fn parse_class<'t>(mut tokens: Tokens<'t>, table: &mut SymbolTable) -> ParserRes
                             Ok((tokens, Located::new(class, span)))
                         }
                     },
-                    None => Err(ParserErr::UnexpectedEndOfInput),
+                    None => {
+                        let class = Class::Identifier(class_identifier);
+                        Ok((tokens, Located::new(class, span)))
+                    }
                 }
             }
             Token::Punctuation(OpenParen) => {
@@ -573,23 +1092,43 @@ fn parse_class<'t>(mut tokens: Tokens<'t>, table: &mut SymbolTable) -> ParserRes
                     }
                     Some(token) => Err(ParserErr::UnrecognizedToken {
                         expected: "')'",
-                        found: token,
+                        found: token.clone(),
                     }),
                     None => Err(ParserErr::UnexpectedEndOfInput),
                 }
             }
             Token::Punctuation(OpenBrace) => parse_enumerated_class(tokens)
-                .map(|(tokens, classes)| (tokens, classes.map(|cs| Class::Enumerated(cs)))),
+                .map(|(tokens, classes)| (tokens, classes.map(Class::Enumerated))),
+            Token::Keyword(NOT) => {
+                tokens.next();
+
+                // NOT applies to the class right after it, or to a whole
+                // parenthesized description; since intersections and unions
+                // start with a class identifier, a complement cannot start
+                // one, and is only a whole description or a filler
+                let (remaining, class) = match tokens.peek().map(|t| &t.value) {
+                    Some(Token::ClassIdentifier { .. }) => parse_class_identifier(tokens)
+                        .map(|(tokens, class)| (tokens, class.map(Class::Identifier)))?,
+                    _ => parse_class(tokens, table)?,
+                };
+                tokens = remaining;
+
+                let span = token.span.merge(&class.span);
+                Ok((
+                    tokens,
+                    Located::new(Class::Complement(Box::new(class.value)), span),
+                ))
+            }
             _ => Err(ParserErr::UnrecognizedToken {
-                expected: "ClassIdentifier or '(' or '{'",
-                found: token,
+                expected: "ClassIdentifier or '(' or '{' or 'NOT'",
+                found: token.clone(),
             }),
         },
         None => Err(ParserErr::UnexpectedEndOfInput),
     }
 }
 
-fn parse_data<'t>(mut tokens: Tokens<'t>) -> ParserResult<'t, Data> {
+fn parse_data<'t>(mut tokens: Tokens<'t>, table: &SymbolTable) -> ParserResult<'t, Data> {
     match tokens.next() {
         Some(token) => match token.value {
             Token::Datatype(datatype) => {
@@ -614,7 +1153,7 @@ fn parse_data<'t>(mut tokens: Tokens<'t>) -> ParserResult<'t, Data> {
                                 _ => {
                                     return Err(ParserErr::UnrecognizedToken {
                                         expected: "'<' or '<=' or '>' or '>='",
-                                        found: token,
+                                        found: token.clone(),
                                     })
                                 }
                             },
@@ -623,6 +1162,11 @@ fn parse_data<'t>(mut tokens: Tokens<'t>) -> ParserResult<'t, Data> {
 
                         let (mut tokens, literal) = parse_literal(tokens)?;
 
+                        let lexical = table.get(literal.value.index).map_or("", |s| s.id());
+                        if !datatype.accepts(lexical) {
+                            return Err(ParserErr::IllTypedLiteral { literal, datatype });
+                        }
+
                         data.restriction = Some(Restriction(restriction, literal.value));
                         match tokens.next() {
                             Some(token) => match token.value {
@@ -632,7 +1176,7 @@ fn parse_data<'t>(mut tokens: Tokens<'t>) -> ParserResult<'t, Data> {
                                 }
                                 _ => Err(ParserErr::UnrecognizedToken {
                                     expected: "']'",
-                                    found: token,
+                                    found: token.clone(),
                                 }),
                             },
                             None => Err(ParserErr::UnexpectedEndOfInput),
@@ -643,7 +1187,7 @@ fn parse_data<'t>(mut tokens: Tokens<'t>) -> ParserResult<'t, Data> {
             }
             _ => Err(ParserErr::UnrecognizedToken {
                 expected: "Datatype",
-                found: token,
+                found: token.clone(),
             }),
         },
         None => Err(ParserErr::UnexpectedEndOfInput),
@@ -661,23 +1205,24 @@ fn parse_literal<'t>(mut tokens: Tokens<'t>) -> ParserResult<'t, Literal> {
 
             _ => Err(ParserErr::UnrecognizedToken {
                 expected: "Literal",
-                found: token,
+                found: token.clone(),
             }),
         },
         None => Err(ParserErr::UnexpectedEndOfInput),
     }
 }
 
-fn assert_property<'t>(
-    property: &Located<'t, Property>,
-    table: &mut SymbolTable,
-) -> Result<(), ParserErr<'t>> {
+fn assert_property(property: &Located<Property>, table: &mut SymbolTable) -> Result<(), ParserErr> {
     if let Some(symbol) = table.get(property.value.index).cloned() {
         let found = Type::Property(Some(property.value.type_()));
         match symbol.type_() {
             Type::Property(type_) => {
                 let type_ = type_.unwrap_or_else(|| {
-                    table.update_property_type(property.value.index, property.value.type_());
+                    table.update_property_type(
+                        property.value.index,
+                        property.value.type_(),
+                        property.span,
+                    );
                     property.value.type_()
                 });
 
@@ -686,6 +1231,7 @@ fn assert_property<'t>(
                         location: property.span,
                         expected: symbol.type_(),
                         found,
+                        previous: symbol.typed_at(),
                     });
                 }
             }
@@ -694,28 +1240,137 @@ fn assert_property<'t>(
                     location: property.span,
                     expected: type_,
                     found,
+                    previous: symbol.typed_at(),
+                })
+            }
+        }
+    }
+    Ok(())
+}
+
+fn assert_property_type(
+    property: &Located<PropertyIdentifier>,
+    expected: PropertyType,
+    table: &mut SymbolTable,
+) -> Result<(), ParserErr> {
+    if let Some(symbol) = table.get(property.value.index).cloned() {
+        match symbol.type_() {
+            Type::Property(None) => {
+                table.update_property_type(property.value.index, expected, property.span);
+            }
+            Type::Property(Some(type_)) if type_ == expected => {}
+            type_ => {
+                return Err(ParserErr::TypeMismatch {
+                    location: property.span,
+                    expected: type_,
+                    found: Type::Property(Some(expected)),
+                    previous: symbol.typed_at(),
+                })
+            }
+        }
+    }
+
+    Ok(())
+}
+
+fn assert_same_property_type(
+    properties: &[PropertyIdentifier],
+    span: Span,
+    table: &SymbolTable,
+) -> Result<(), ParserErr> {
+    // disjoint properties must all be object properties or all data properties
+
+    let mut expected: Option<(PropertyType, Option<Span>)> = None;
+
+    for property in properties {
+        let Some(symbol) = table.get(property.index) else {
+            continue;
+        };
+
+        let Type::Property(Some(type_)) = symbol.type_() else {
+            continue;
+        };
+
+        match expected {
+            None => expected = Some((type_, symbol.typed_at())),
+            Some((expected, previous)) if expected != type_ => {
+                return Err(ParserErr::TypeMismatch {
+                    location: span,
+                    expected: Type::Property(Some(expected)),
+                    found: Type::Property(Some(type_)),
+                    previous,
                 })
             }
+            Some(_) => {}
         }
     }
+
     Ok(())
 }
 
+fn values<T>(located: &[Located<T>]) -> Vec<&T> {
+    located.iter().map(|l| &l.value).collect()
+}
+
+// declarations and descriptions are equal when their restrictions are,
+// wherever each restriction was written
+impl PartialEq for ClassDecl {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (Self::Defined(a, p), Self::Defined(b, q))
+            | (Self::Primitive(a, p), Self::Primitive(b, q)) => a == b && values(p) == values(q),
+            (Self::Enumerated(a), Self::Enumerated(b)) | (Self::Union(a), Self::Union(b)) => a == b,
+            _ => false,
+        }
+    }
+}
+
+impl Eq for ClassDecl {}
+
+impl PartialEq for Class {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (Self::Identifier(a), Self::Identifier(b)) => a == b,
+            (Self::Defined(a, p), Self::Defined(b, q)) => a == b && values(p) == values(q),
+            (Self::Enumerated(a), Self::Enumerated(b)) | (Self::Union(a), Self::Union(b)) => a == b,
+            (Self::Complement(a), Self::Complement(b)) => a == b,
+            _ => false,
+        }
+    }
+}
+
+impl Eq for Class {}
+
+impl PartialEq for DataDescription {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (Self::Some(a), Self::Some(b)) | (Self::Only(a), Self::Only(b)) => a == b,
+            (Self::Value(a), Self::Value(b)) => a.value == b.value,
+            (Self::Min(n, a), Self::Min(m, b))
+            | (Self::Max(n, a), Self::Max(m, b))
+            | (Self::Exactly(n, a), Self::Exactly(m, b)) => n == m && a == b,
+            _ => false,
+        }
+    }
+}
+
+impl Eq for DataDescription {}
+
 impl Debug for ClassDecl {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
             Self::Defined(super_class, properties) => f
                 .debug_struct("DefinedClass")
                 .field("super_class", super_class)
-                .field("properties", properties)
+                .field("properties", &values(properties))
                 .finish(),
             Self::Primitive(super_class, properties) => f
                 .debug_struct("PrimitiveClass")
                 .field("super_class", super_class)
-                .field("properties", properties)
+                .field("properties", &values(properties))
                 .finish(),
             Self::Enumerated(classes) => f.debug_tuple("EnumeratedClass").field(classes).finish(),
-            Self::Disjoint(classes) => f.debug_tuple("DisjointClass").field(classes).finish(),
+            Self::Union(classes) => f.debug_tuple("UnionClass").field(classes).finish(),
         }
     }
 }
@@ -727,10 +1382,86 @@ impl Debug for Class {
             Self::Defined(super_class, properties) => f
                 .debug_struct("DefinedClass")
                 .field("super_class", super_class)
-                .field("properties", properties)
+                .field("properties", &values(properties))
                 .finish(),
             Self::Enumerated(classes) => f.debug_tuple("EnumeratedClass").field(classes).finish(),
-            Self::Disjoint(classes) => f.debug_tuple("DisjointClass").field(classes).finish(),
+            Self::Union(classes) => f.debug_tuple("UnionClass").field(classes).finish(),
+            Self::Complement(class) => f.debug_tuple("ComplementClass").field(class).finish(),
+        }
+    }
+}
+
+impl Debug for Frame {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Class {
+                class,
+                annotations,
+                super_classes,
+            } => f
+                .debug_struct("ClassFrame")
+                .field("class", class)
+                .field("annotations", annotations)
+                .field("super_classes", &values(super_classes))
+                .finish(),
+            Self::AnnotationProperty {
+                property,
+                annotations,
+            } => f
+                .debug_struct("AnnotationPropertyFrame")
+                .field("property", property)
+                .field("annotations", annotations)
+                .finish(),
+            Self::Decl { annotations, decl } if annotations.is_empty() => decl.value.fmt(f),
+            Self::ObjectProperty {
+                property,
+                annotations,
+                chains,
+            } => f
+                .debug_struct("ObjectPropertyFrame")
+                .field("property", property)
+                .field("annotations", annotations)
+                .field("chains", chains)
+                .finish(),
+            Self::DataProperty {
+                property,
+                annotations,
+                ranges,
+            } => f
+                .debug_struct("DataPropertyFrame")
+                .field("property", property)
+                .field("annotations", annotations)
+                .field("ranges", &values(ranges))
+                .finish(),
+            Self::DisjointClasses(misc) => f.debug_tuple("DisjointClasses").field(misc).finish(),
+            Self::EquivalentClasses(misc) => {
+                f.debug_tuple("EquivalentClasses").field(misc).finish()
+            }
+            Self::DisjointProperties(misc) => {
+                f.debug_tuple("DisjointProperties").field(misc).finish()
+            }
+            Self::SameIndividual(misc) => f.debug_tuple("SameIndividual").field(misc).finish(),
+            Self::DifferentIndividuals(misc) => {
+                f.debug_tuple("DifferentIndividuals").field(misc).finish()
+            }
+            Self::Decl { annotations, decl } => f
+                .debug_struct("AnnotatedDecl")
+                .field("annotations", annotations)
+                .field("decl", &decl.value)
+                .finish(),
+        }
+    }
+}
+
+impl<T: Debug> Debug for Misc<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.annotations.is_empty() {
+            f.debug_list().entries(&self.members).finish()
+        } else {
+            f.debug_struct("Misc")
+                .field("annotations", &self.annotations)
+                .field("members", &self.members)
+                .finish()
         }
     }
 }
@@ -741,6 +1472,12 @@ impl Debug for ClassIdentifier {
     }
 }
 
+impl Debug for IndividualIdentifier {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "IndividualIdentifier {{ index: {} }}", self.index)
+    }
+}
+
 impl Debug for PropertyDescription {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
@@ -750,6 +1487,35 @@ impl Debug for PropertyDescription {
     }
 }
 
+impl Debug for PropertyIdentifier {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "PropertyIdentifier {{ index: {} }}", self.index)
+    }
+}
+
+impl Debug for AnnotationPropertyIdentifier {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "AnnotationPropertyIdentifier {{ index: {} }}",
+            self.index
+        )
+    }
+}
+
+impl Debug for DataDescription {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Some(data) => f.debug_tuple("Some").field(data).finish(),
+            Self::Only(data) => f.debug_tuple("Only").field(data).finish(),
+            Self::Value(literal) => f.debug_tuple("Value").field(&literal.value).finish(),
+            Self::Min(n, data) => f.debug_tuple("Min").field(n).field(data).finish(),
+            Self::Max(n, data) => f.debug_tuple("Max").field(n).field(data).finish(),
+            Self::Exactly(n, data) => f.debug_tuple("Exactly").field(n).field(data).finish(),
+        }
+    }
+}
+
 impl Debug for Literal {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(f, "Literal {{ index: {} }}", self.index)
@@ -772,49 +1538,94 @@ impl Debug for Data {
     }
 }
 
-impl Debug for ParserErr<'_> {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+impl ParserErr {
+    pub fn describe(&self, map: &SourceMap) -> String {
         match self {
-            Self::UnexpectedEndOfInput => write!(f, "UnexpectedEndOfInput"),
-            Self::RepeatedProperty(property) => {
-                write!(
-                    f,
-                    "{}: RepeatedProperty: {:?}",
-                    property.span.location(),
-                    property.span.fragment()
-                )
-            }
-            Self::RepeatedClass(arg0) => {
-                write!(
-                    f,
-                    "{}: RepeatedClass: {:?}",
-                    arg0.span.location(),
-                    arg0.span.fragment()
-                )
-            }
-            Self::UnrecognizedToken { expected, found } => {
-                write!(
-                    f,
-                    "{}: UnrecognizedToken: expected {}, found {:?}",
-                    found.span.location(),
-                    expected,
-                    found.value
-                )
-            }
+            Self::UnexpectedEndOfInput => "UnexpectedEndOfInput".to_string(),
+            Self::RepeatedClass(arg0) => format!(
+                "{}: RepeatedClass: {:?}",
+                map.location(arg0.span),
+                map.fragment(arg0.span)
+            ),
+            Self::UnrecognizedToken { expected, found } => format!(
+                "{}: UnrecognizedToken: expected {}, found {:?}",
+                map.location(found.span),
+                expected,
+                found.value
+            ),
             Self::TypeMismatch {
                 location,
                 expected,
                 found,
+                previous,
             } => {
-                write!(
-                    f,
+                let mut message = format!(
                     "{}: TypeMismatch: expected {:?}, found {:?} at '{}'",
-                    location.location(),
+                    map.location(*location),
                     expected,
                     found,
-                    location.fragment()
-                )
+                    map.fragment(*location)
+                );
+
+                if let Some(previous) = previous {
+                    message += &format!(
+                        " (typed as {:?} at {}: '{}')",
+                        expected,
+                        map.location(*previous),
+                        map.fragment(*previous)
+                    );
+                }
+
+                message
             }
+            Self::IllTypedLiteral { literal, datatype } => format!(
+                "{}: IllTypedLiteral: {} is not a valid {:?}",
+                map.location(literal.span),
+                map.fragment(literal.span),
+                datatype
+            ),
+        }
+    }
+}
+
+impl Annotation {
+    /// Renders the annotation as written in Manchester syntax.
+    pub fn describe(&self, table: &SymbolTable) -> String {
+        let property = table.get(self.property.index).map_or("?", |s| s.id());
+
+        match &self.value {
+            AnnotationValue::Literal(literal, language) => {
+                let literal = table.get(literal.index).map_or("?", |s| s.id());
+
+                match language {
+                    Some(language) => format!("{} \"{}\"@{}", property, literal, language),
+                    None => format!("{} \"{}\"", property, literal),
+                }
+            }
+            AnnotationValue::Iri(iri) => format!("{} <{}>", property, iri),
+        }
+    }
+}
+
+impl RestrictionType {
+    /// The facet as written in a datatype restriction.
+    pub fn symbol(&self) -> &'static str {
+        match self {
+            Self::LessThanEqual => "<=",
+            Self::GreaterThanEqual => ">=",
+            Self::LessThan => "<",
+            Self::GreaterThan => ">",
+        }
+    }
+
+    /// The local name of the XML Schema facet, such as `minInclusive` for
+    /// `>=`.
+    pub fn facet(&self) -> &'static str {
+        match self {
+            Self::LessThanEqual => "maxInclusive",
+            Self::GreaterThanEqual => "minInclusive",
+            Self::LessThan => "maxExclusive",
+            Self::GreaterThan => "minExclusive",
         }
     }
 }
@@ -0,0 +1,449 @@
+use crate::{
+    parser::{
+        Annotation, Class, ClassDecl, Data, DataDescription, Frame, ObjectDescription, Ontology,
+        Property, PropertyDescription,
+    },
+    table::SymbolTable,
+};
+
+/// Layout of printed Manchester syntax.
+#[derive(Debug, Clone, Copy)]
+pub struct Style {
+    /// Spaces per level of indentation.
+    pub indent: usize,
+    /// Column past which parenthesized descriptions are broken over lines.
+    pub width: usize,
+}
+
+impl Default for Style {
+    fn default() -> Self {
+        Self {
+            indent: 4,
+            width: 80,
+        }
+    }
+}
+
+// text to be laid out, with the parenthesized groups that may be broken
+enum Doc {
+    Text(String),
+    Concat(Vec<Doc>),
+    /// `(...)`, kept on one line if it fits and otherwise opened at the end
+    /// of the line, with its contents indented and `)` on a line of its own.
+    Group(Box<Doc>),
+}
+
+impl Doc {
+    fn flat(&self) -> String {
+        match self {
+            Self::Text(text) => text.clone(),
+            Self::Concat(docs) => docs.iter().map(Self::flat).collect(),
+            Self::Group(doc) => format!("({})", doc.flat()),
+        }
+    }
+}
+
+struct Printer<'a> {
+    table: &'a SymbolTable,
+    style: Style,
+    out: String,
+}
+
+/// Renders an ontology in canonical Manchester syntax: one frame after the
+/// other, with a blank line around frames that span several lines.
+pub fn ontology(ontology: &Ontology, table: &SymbolTable, style: Style) -> String {
+    let mut header = String::new();
+
+    if let Some(iri) = &ontology.iri {
+        header += &format!("Ontology: <{}>\n", iri.value);
+    }
+
+    for import in &ontology.imports {
+        header += &format!("Import: <{}>\n", import.value);
+    }
+
+    let frames = ontology
+        .frames
+        .iter()
+        .map(|frame| self::frame(&frame.value, table, style))
+        .collect::<Vec<_>>();
+
+    let mut out = header;
+
+    for (i, frame) in frames.iter().enumerate() {
+        let multiline = |frame: &String| frame.trim_end().contains('\n');
+
+        if i == 0 && !out.is_empty() || i > 0 && (multiline(frame) || multiline(&frames[i - 1])) {
+            out.push('\n');
+        }
+
+        out += frame;
+    }
+
+    out
+}
+
+/// Renders a frame, ending with a newline.
+pub fn frame(frame: &Frame, table: &SymbolTable, style: Style) -> String {
+    let mut printer = Printer::new(table, style);
+    printer.frame(frame);
+    printer.out
+}
+
+/// Renders a class declaration.
+pub fn decl(decl: &ClassDecl, table: &SymbolTable, style: Style) -> String {
+    let mut printer = Printer::new(table, style);
+    printer.decl(decl);
+    printer.out
+}
+
+/// Renders a class description.
+pub fn class(class: &Class, table: &SymbolTable, style: Style) -> String {
+    let mut printer = Printer::new(table, style);
+    let doc = printer.class(class);
+    printer.layout(&doc, 0);
+    printer.out
+}
+
+impl<'a> Printer<'a> {
+    fn new(table: &'a SymbolTable, style: Style) -> Self {
+        Self {
+            table,
+            style,
+            out: String::new(),
+        }
+    }
+
+    fn id(&self, index: usize) -> String {
+        self.table.get(index).map_or("?", |s| s.id()).to_string()
+    }
+
+    fn frame(&mut self, frame: &Frame) {
+        match frame {
+            Frame::Class {
+                class,
+                annotations,
+                super_classes,
+            } => {
+                self.out += &format!("Class: {}\n", self.id(class.index));
+                self.annotations(annotations, 1);
+
+                if !super_classes.is_empty() {
+                    let mut docs = vec![Doc::Text("SubClassOf: ".to_string())];
+
+                    for (i, super_class) in super_classes.iter().enumerate() {
+                        if i > 0 {
+                            docs.push(Doc::Text(", ".to_string()));
+                        }
+                        docs.push(self.class(&super_class.value));
+                    }
+
+                    self.line(&Doc::Concat(docs), 1);
+                }
+            }
+            Frame::AnnotationProperty {
+                property,
+                annotations,
+            } => {
+                self.out += &format!("AnnotationProperty: {}\n", self.id(property.index));
+                self.annotations(annotations, 1);
+            }
+            Frame::ObjectProperty {
+                property,
+                annotations,
+                chains,
+            } => {
+                self.out += &format!("ObjectProperty: {}\n", self.id(property.index));
+                self.annotations(annotations, 1);
+
+                for chain in chains {
+                    let properties = chain
+                        .0
+                        .iter()
+                        .map(|property| self.id(property.index))
+                        .collect::<Vec<_>>();
+                    let text = format!("SubPropertyChain: {}", properties.join(" o "));
+                    self.line(&Doc::Text(text), 1);
+                }
+            }
+            Frame::DataProperty {
+                property,
+                annotations,
+                ranges,
+            } => {
+                self.out += &format!("DataProperty: {}\n", self.id(property.index));
+                self.annotations(annotations, 1);
+
+                for range in ranges {
+                    let text = format!("Range: {}", self.data(&range.value));
+                    self.line(&Doc::Text(text), 1);
+                }
+            }
+            Frame::Decl { annotations, decl } => {
+                self.annotations(annotations, 0);
+                self.decl(&decl.value);
+            }
+            Frame::DisjointClasses(misc) | Frame::EquivalentClasses(misc) => {
+                let section = match frame {
+                    Frame::DisjointClasses(_) => "DisjointClasses:",
+                    _ => "EquivalentClasses:",
+                };
+                let members = misc
+                    .members
+                    .iter()
+                    .map(|member| self.class(member))
+                    .collect();
+                self.misc(section, &misc.annotations, members);
+            }
+            Frame::DisjointProperties(misc) => {
+                let members = misc
+                    .members
+                    .iter()
+                    .map(|member| Doc::Text(self.id(member.index)))
+                    .collect();
+                self.misc("DisjointProperties:", &misc.annotations, members);
+            }
+            Frame::SameIndividual(misc) | Frame::DifferentIndividuals(misc) => {
+                let section = match frame {
+                    Frame::SameIndividual(_) => "SameIndividual:",
+                    _ => "DifferentIndividuals:",
+                };
+                let members = misc
+                    .members
+                    .iter()
+                    .map(|member| Doc::Text(self.id(member.index)))
+                    .collect();
+                self.misc(section, &misc.annotations, members);
+            }
+        }
+    }
+
+    fn misc(&mut self, section: &str, annotations: &[Annotation], members: Vec<Doc>) {
+        let mut docs = Vec::new();
+
+        for (i, member) in members.into_iter().enumerate() {
+            if i > 0 {
+                docs.push(Doc::Text(", ".to_string()));
+            }
+            docs.push(member);
+        }
+
+        if annotations.is_empty() {
+            docs.insert(0, Doc::Text(format!("{} ", section)));
+            self.line(&Doc::Concat(docs), 0);
+        } else {
+            self.out += &format!("{}\n", section);
+            self.annotations(annotations, 1);
+            self.line(&Doc::Concat(docs), 1);
+        }
+    }
+
+    fn annotations(&mut self, annotations: &[Annotation], depth: usize) {
+        if !annotations.is_empty() {
+            let annotations = annotations
+                .iter()
+                .map(|annotation| annotation.describe(self.table))
+                .collect::<Vec<_>>();
+            let text = format!("Annotations: {}", annotations.join(", "));
+            self.line(&Doc::Text(text), depth);
+        }
+    }
+
+    fn decl(&mut self, decl: &ClassDecl) {
+        match decl {
+            ClassDecl::Defined(class, properties) => {
+                let mut docs = vec![Doc::Text(self.id(class.index))];
+
+                for property in properties {
+                    docs.push(Doc::Text(" AND ".to_string()));
+                    docs.push(Doc::Group(Box::new(self.property(&property.value))));
+                }
+
+                self.line(&Doc::Concat(docs), 0);
+            }
+            // the restrictions of a primitive class go on lines of their own
+            ClassDecl::Primitive(class, properties) => {
+                self.out += &format!("{}\n", self.id(class.index));
+
+                for property in properties {
+                    let doc = self.property(&property.value);
+                    self.line(&doc, 0);
+                }
+            }
+            ClassDecl::Enumerated(classes) => {
+                let text = self.enumeration(classes.iter().map(|class| class.index));
+                self.out += &format!("{}\n", text);
+            }
+            ClassDecl::Union(classes) => {
+                let classes = classes
+                    .iter()
+                    .map(|class| self.id(class.index))
+                    .collect::<Vec<_>>();
+                self.out += &format!("{}\n", classes.join(" OR "));
+            }
+        }
+    }
+
+    fn class(&self, class: &Class) -> Doc {
+        match class {
+            Class::Identifier(class) => Doc::Text(self.id(class.index)),
+            Class::Defined(class, properties) => {
+                let mut docs = vec![Doc::Text(self.id(class.index))];
+
+                for property in properties {
+                    docs.push(Doc::Text(" AND ".to_string()));
+                    docs.push(Doc::Group(Box::new(self.property(&property.value))));
+                }
+
+                Doc::Concat(docs)
+            }
+            Class::Enumerated(classes) => {
+                Doc::Text(self.enumeration(classes.iter().map(|class| class.index)))
+            }
+            Class::Union(classes) => {
+                let classes = classes
+                    .iter()
+                    .map(|class| self.id(class.index))
+                    .collect::<Vec<_>>();
+                Doc::Text(classes.join(" OR "))
+            }
+            Class::Complement(class) => {
+                Doc::Concat(vec![Doc::Text("NOT ".to_string()), self.operand(class)])
+            }
+        }
+    }
+
+    // a class inside a restriction or a complement, in parentheses unless
+    // it is atomic
+    fn operand(&self, class: &Class) -> Doc {
+        match class {
+            Class::Identifier(_) | Class::Enumerated(_) => self.class(class),
+            Class::Complement(inner) if matches!(**inner, Class::Identifier(_)) => {
+                self.class(class)
+            }
+            _ => Doc::Group(Box::new(self.class(class))),
+        }
+    }
+
+    fn property(&self, property: &Property) -> Doc {
+        let name = self.id(property.index);
+        let restriction = |keyword: &str, filler: Doc| {
+            Doc::Concat(vec![Doc::Text(format!("{} {} ", name, keyword)), filler])
+        };
+
+        match &property.description {
+            PropertyDescription::Object(description) => match description {
+                ObjectDescription::Some(class) => restriction("SOME", self.operand(class)),
+                ObjectDescription::Only(class) => restriction("ONLY", self.operand(class)),
+                ObjectDescription::Value(individual) => {
+                    restriction("VALUE", Doc::Text(self.id(individual.index)))
+                }
+                ObjectDescription::Min(n, class) => {
+                    restriction(&format!("MIN {}", n), self.operand(class))
+                }
+                ObjectDescription::Max(n, class) => {
+                    restriction(&format!("MAX {}", n), self.operand(class))
+                }
+                ObjectDescription::Exactly(n, class) => {
+                    restriction(&format!("EXACTLY {}", n), self.operand(class))
+                }
+            },
+            PropertyDescription::Data(description) => match description {
+                DataDescription::Some(data) => restriction("SOME", Doc::Text(self.data(data))),
+                DataDescription::Only(data) => restriction("ONLY", Doc::Text(self.data(data))),
+                DataDescription::Value(literal) => {
+                    let literal = format!("\"{}\"", self.id(literal.value.index));
+                    restriction("VALUE", Doc::Text(literal))
+                }
+                DataDescription::Min(n, data) => {
+                    restriction(&format!("MIN {}", n), Doc::Text(self.data(data)))
+                }
+                DataDescription::Max(n, data) => {
+                    restriction(&format!("MAX {}", n), Doc::Text(self.data(data)))
+                }
+                DataDescription::Exactly(n, data) => {
+                    restriction(&format!("EXACTLY {}", n), Doc::Text(self.data(data)))
+                }
+            },
+        }
+    }
+
+    fn data(&self, data: &Data) -> String {
+        match &data.restriction {
+            Some(restriction) => format!(
+                "{}[{}\"{}\"]",
+                data.datatype.name(),
+                restriction.0.symbol(),
+                self.id(restriction.1.index)
+            ),
+            None => data.datatype.name().to_string(),
+        }
+    }
+
+    fn enumeration(&self, individuals: impl Iterator<Item = usize>) -> String {
+        let individuals = individuals.map(|i| self.id(i)).collect::<Vec<_>>();
+        format!("{{{}}}", individuals.join(", "))
+    }
+
+    // writes a line at the given depth of indentation
+    fn line(&mut self, doc: &Doc, depth: usize) {
+        self.out += &" ".repeat(depth * self.style.indent);
+        self.layout(doc, depth);
+        self.out.push('\n');
+    }
+
+    fn layout(&mut self, doc: &Doc, depth: usize) {
+        match doc {
+            Doc::Text(text) => self.out += text,
+            Doc::Concat(docs) => {
+                for doc in docs {
+                    self.layout(doc, depth);
+                }
+            }
+            Doc::Group(inner) => {
+                let flat = doc.flat();
+                let line = self.out.rfind('\n').map_or(0, |i| i + 1);
+                let column = self.out[line..].chars().count();
+
+                if column + flat.chars().count() <= self.style.width {
+                    self.out += &flat;
+                } else {
+                    let indent = " ".repeat(self.style.indent);
+                    self.out.push_str("(\n");
+                    self.out += &indent.repeat(depth + 1);
+                    self.layout(inner, depth + 1);
+                    self.out.push('\n');
+                    self.out += &indent.repeat(depth);
+                    self.out.push(')');
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{lexer, parser, span::SourceMap};
+
+    fn format(text: &str) -> String {
+        let mut map = SourceMap::new();
+        let mut table = SymbolTable::new();
+        let file_id = map.add("test.txt".to_string(), text.to_string());
+
+        let Ok(tokens) = lexer::parse(&map, file_id, &mut table) else {
+            panic!("could not lex {:?}", text);
+        };
+        let Ok(parsed) = parser::parse(&tokens, &mut table) else {
+            panic!("could not parse {:?}", text);
+        };
+        ontology(&parsed, &table, Style::default())
+    }
+
+    #[test]
+    fn formatting_is_idempotent() {
+        let formatted = format(include_str!("../file1.txt"));
+
+        assert_eq!(format(&formatted), formatted);
+    }
+}
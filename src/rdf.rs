@@ -0,0 +1,717 @@
+use std::collections::{BTreeSet, HashMap};
+
+use crate::{
+    functional::{self, PREFIXES},
+    lexer::Datatype,
+    parser::{
+        Annotation, AnnotationValue, Class, ClassDecl, Data, DataDescription, Frame,
+        ObjectDescription, Ontology, Property, PropertyDescription,
+    },
+    span::Located,
+    table::SymbolTable,
+};
+
+const OWL: &str = "http://www.w3.org/2002/07/owl#";
+const RDF: &str = "http://www.w3.org/1999/02/22-rdf-syntax-ns#";
+const RDFS: &str = "http://www.w3.org/2000/01/rdf-schema#";
+const XSD: &str = "http://www.w3.org/2001/XMLSchema#";
+
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Term {
+    Iri(String),
+    /// A blank node, by its number in the graph.
+    Blank(usize),
+    Literal(String, Tag),
+}
+
+/// What a literal is tagged with.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Tag {
+    /// The IRI of its datatype.
+    Datatype(String),
+    Language(String),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Triple {
+    pub subject: Term,
+    /// The IRI of the predicate.
+    pub predicate: String,
+    pub object: Term,
+}
+
+struct Builder<'a> {
+    table: &'a SymbolTable,
+    namespace: &'a str,
+    datatypes: HashMap<usize, Datatype>,
+    // (OWL type, IRI) of the entities to declare
+    entities: BTreeSet<(&'static str, String)>,
+    triples: Vec<Triple>,
+    blanks: usize,
+    declarations: usize,
+}
+
+/// Maps an ontology to RDF triples following the OWL 2 mapping to RDF
+/// graphs, with its classes, properties and individuals in `namespace`.
+///
+/// Class expressions become blank nodes, `AND`, `OR` and enumerations RDF
+/// lists, and literals are typed with the datatype of the context they
+/// appear in. As in `functional::ontology`, the anonymous class of the n-th
+/// declaration is named `declaration<n>`.
+pub fn triples(ontology: &Ontology, table: &SymbolTable, namespace: &str) -> Vec<Triple> {
+    let mut builder = Builder {
+        table,
+        namespace,
+        datatypes: functional::datatypes(ontology),
+        entities: BTreeSet::new(),
+        triples: Vec::new(),
+        blanks: 0,
+        declarations: 0,
+    };
+
+    let root = match &ontology.iri {
+        Some(iri) => Term::Iri(iri.value.clone()),
+        None => builder.blank(),
+    };
+
+    for frame in &ontology.frames {
+        builder.frame(&frame.value);
+    }
+
+    let mut header = vec![Triple {
+        subject: root.clone(),
+        predicate: format!("{}type", RDF),
+        object: Term::Iri(format!("{}Ontology", OWL)),
+    }];
+
+    for import in &ontology.imports {
+        header.push(Triple {
+            subject: root.clone(),
+            predicate: format!("{}imports", OWL),
+            object: Term::Iri(import.value.clone()),
+        });
+    }
+
+    for (type_, iri) in &builder.entities {
+        header.push(Triple {
+            subject: Term::Iri(iri.clone()),
+            predicate: format!("{}type", RDF),
+            object: Term::Iri(format!("{}{}", OWL, type_)),
+        });
+    }
+
+    header.extend(builder.triples);
+    header
+}
+
+/// Writes triples in N-Triples, one per line with full IRIs.
+pub fn ntriples(triples: &[Triple]) -> String {
+    let term = |term: &Term| match term {
+        Term::Iri(iri) => format!("<{}>", iri),
+        Term::Blank(n) => format!("_:b{}", n),
+        Term::Literal(lexical, Tag::Datatype(datatype)) => {
+            format!("{}^^<{}>", functional::quote(lexical), datatype)
+        }
+        Term::Literal(lexical, Tag::Language(language)) => {
+            format!("{}@{}", functional::quote(lexical), language)
+        }
+    };
+
+    triples
+        .iter()
+        .map(|triple| {
+            format!(
+                "{} <{}> {} .\n",
+                term(&triple.subject),
+                triple.predicate,
+                term(&triple.object)
+            )
+        })
+        .collect()
+}
+
+/// Writes triples in Turtle, with the IRIs in `namespace` and in the
+/// well-known namespaces as prefixed names, and the consecutive triples of a
+/// subject grouped with `;`.
+pub fn turtle(triples: &[Triple], namespace: &str) -> String {
+    let mut prefixes = vec![("", namespace.to_string())];
+    prefixes.extend(
+        PREFIXES
+            .iter()
+            .map(|(prefix, iri)| (*prefix, iri.to_string())),
+    );
+
+    let mut used = BTreeSet::new();
+
+    let mut iri = |iri: &str| {
+        for (i, (prefix, namespace)) in prefixes.iter().enumerate() {
+            if let Some(local) = iri.strip_prefix(namespace.as_str()) {
+                let mut chars = local.chars();
+                let valid = chars.next().is_some_and(|c| c.is_alphabetic() || c == '_')
+                    && chars.all(|c| c.is_alphanumeric() || c == '_' || c == '-');
+
+                if valid {
+                    used.insert(i);
+                    return format!("{}:{}", prefix, local);
+                }
+            }
+        }
+
+        format!("<{}>", iri)
+    };
+
+    let mut body = String::new();
+    let mut subject: Option<&Term> = None;
+
+    for triple in triples {
+        let predicate = if triple.predicate == format!("{}type", RDF) {
+            "a".to_string()
+        } else {
+            iri(&triple.predicate)
+        };
+
+        let object = match &triple.object {
+            Term::Iri(object) => iri(object),
+            Term::Blank(n) => format!("_:b{}", n),
+            Term::Literal(lexical, Tag::Datatype(datatype)) => {
+                format!("{}^^{}", functional::quote(lexical), iri(datatype))
+            }
+            Term::Literal(lexical, Tag::Language(language)) => {
+                format!("{}@{}", functional::quote(lexical), language)
+            }
+        };
+
+        if subject == Some(&triple.subject) {
+            body += &format!(" ;\n    {} {}", predicate, object);
+        } else {
+            if subject.is_some() {
+                body += " .\n";
+            }
+
+            let name = match &triple.subject {
+                Term::Iri(subject) => iri(subject),
+                Term::Blank(n) => format!("_:b{}", n),
+                Term::Literal(..) => unreachable!("literals are never subjects"),
+            };
+
+            body += &format!("{} {} {}", name, predicate, object);
+            subject = Some(&triple.subject);
+        }
+    }
+
+    if subject.is_some() {
+        body += " .\n";
+    }
+
+    let mut out = String::new();
+
+    for i in used {
+        let (prefix, namespace) = &prefixes[i];
+        out += &format!("@prefix {}: <{}> .\n", prefix, namespace);
+    }
+
+    if !out.is_empty() {
+        out.push('\n');
+    }
+
+    out + &body
+}
+
+impl<'a> Builder<'a> {
+    fn id(&self, index: usize) -> &'a str {
+        self.table.get(index).map_or("?", |s| s.id())
+    }
+
+    fn blank(&mut self) -> Term {
+        self.blanks += 1;
+        Term::Blank(self.blanks)
+    }
+
+    fn add(&mut self, subject: &Term, predicate: String, object: Term) {
+        self.triples.push(Triple {
+            subject: subject.clone(),
+            predicate,
+            object,
+        });
+    }
+
+    // the IRI of a symbol, declared with the given type
+    fn entity(&mut self, type_: &'static str, index: usize) -> Term {
+        let iri = format!("{}{}", self.namespace, self.id(index));
+        self.entities.insert((type_, iri.clone()));
+        Term::Iri(iri)
+    }
+
+    fn class_iri(&mut self, index: usize) -> Term {
+        self.entity("Class", index)
+    }
+
+    fn individual(&mut self, index: usize) -> Term {
+        self.entity("NamedIndividual", index)
+    }
+
+    fn object_property(&mut self, index: usize) -> Term {
+        self.entity("ObjectProperty", index)
+    }
+
+    fn data_property(&mut self, index: usize) -> Term {
+        self.entity("DatatypeProperty", index)
+    }
+
+    fn annotation_property(&mut self, index: usize) -> String {
+        let id = self.id(index);
+        let iri = match functional::annotation_prefix(id) {
+            Some((prefix, local)) => {
+                format!("{}{}", functional::prefix(prefix, self.namespace), local)
+            }
+            None => format!("{}{}", self.namespace, id),
+        };
+
+        self.entities.insert(("AnnotationProperty", iri.clone()));
+        iri
+    }
+
+    fn frame(&mut self, frame: &Frame) {
+        match frame {
+            Frame::Class {
+                class,
+                annotations,
+                super_classes,
+            } => {
+                let class = self.class_iri(class.index);
+                self.assertions(&class, annotations);
+
+                for super_class in super_classes {
+                    let super_class = self.class(&super_class.value);
+                    self.add(&class, format!("{}subClassOf", RDFS), super_class);
+                }
+            }
+            Frame::AnnotationProperty {
+                property,
+                annotations,
+            } => {
+                let property = Term::Iri(self.annotation_property(property.index));
+                self.assertions(&property, annotations);
+            }
+            Frame::Decl { annotations, decl } => {
+                self.declarations += 1;
+                let iri = format!("{}declaration{}", self.namespace, self.declarations);
+                self.entities.insert(("Class", iri.clone()));
+                let class = Term::Iri(iri);
+                self.assertions(&class, annotations);
+
+                let (predicate, description) = match &decl.value {
+                    ClassDecl::Defined(super_class, properties) => (
+                        format!("{}equivalentClass", OWL),
+                        self.intersection(super_class.index, properties),
+                    ),
+                    ClassDecl::Primitive(super_class, properties) => (
+                        format!("{}subClassOf", RDFS),
+                        self.intersection(super_class.index, properties),
+                    ),
+                    ClassDecl::Enumerated(individuals) => (
+                        format!("{}equivalentClass", OWL),
+                        self.one_of(individuals.iter().map(|i| i.index)),
+                    ),
+                    ClassDecl::Union(classes) => (
+                        format!("{}equivalentClass", OWL),
+                        self.union(classes.iter().map(|c| c.index)),
+                    ),
+                };
+
+                self.add(&class, predicate, description);
+            }
+            Frame::ObjectProperty {
+                property,
+                annotations,
+                chains,
+            } => {
+                let property = self.object_property(property.index);
+                self.assertions(&property, annotations);
+
+                for chain in chains {
+                    let properties = chain
+                        .0
+                        .iter()
+                        .map(|p| self.object_property(p.index))
+                        .collect();
+                    let list = self.list(properties);
+                    self.add(&property, format!("{}propertyChainAxiom", OWL), list);
+                }
+            }
+            Frame::DataProperty {
+                property,
+                annotations,
+                ranges,
+            } => {
+                let property = self.data_property(property.index);
+                self.assertions(&property, annotations);
+
+                for range in ranges {
+                    let range = self.data(&range.value);
+                    self.add(&property, format!("{}range", RDFS), range);
+                }
+            }
+            Frame::EquivalentClasses(misc) => {
+                let members = misc.members.iter().map(|m| self.class(m)).collect();
+                self.pairwise(
+                    &misc.annotations,
+                    members,
+                    format!("{}equivalentClass", OWL),
+                );
+            }
+            Frame::DisjointClasses(misc) => {
+                let members = misc.members.iter().map(|m| self.class(m)).collect();
+                self.disjoint(
+                    &misc.annotations,
+                    members,
+                    format!("{}disjointWith", OWL),
+                    "AllDisjointClasses",
+                    "members",
+                );
+            }
+            Frame::DisjointProperties(misc) => {
+                let data = functional::disjoint_data_properties(&misc.members, self.table);
+
+                let members = misc
+                    .members
+                    .iter()
+                    .map(|member| match data {
+                        true => self.data_property(member.index),
+                        false => self.object_property(member.index),
+                    })
+                    .collect();
+
+                self.disjoint(
+                    &misc.annotations,
+                    members,
+                    format!("{}propertyDisjointWith", OWL),
+                    "AllDisjointProperties",
+                    "members",
+                );
+            }
+            Frame::SameIndividual(misc) => {
+                let members = misc
+                    .members
+                    .iter()
+                    .map(|m| self.individual(m.index))
+                    .collect();
+                self.pairwise(&misc.annotations, members, format!("{}sameAs", OWL));
+            }
+            Frame::DifferentIndividuals(misc) => {
+                let members = misc
+                    .members
+                    .iter()
+                    .map(|m| self.individual(m.index))
+                    .collect();
+                self.disjoint(
+                    &misc.annotations,
+                    members,
+                    format!("{}differentFrom", OWL),
+                    "AllDifferent",
+                    "distinctMembers",
+                );
+            }
+        }
+    }
+
+    // relates each member to the next one, as the mapping does for
+    // equivalences and `SameIndividual`
+    fn pairwise(&mut self, annotations: &[Annotation], members: Vec<Term>, predicate: String) {
+        for pair in members.windows(2) {
+            self.add(&pair[0], predicate.clone(), pair[1].clone());
+            self.reify(annotations, &pair[0], &predicate, &pair[1]);
+        }
+    }
+
+    // a single triple for two members, or a blank node of type `all` with
+    // the list of members otherwise
+    fn disjoint(
+        &mut self,
+        annotations: &[Annotation],
+        members: Vec<Term>,
+        predicate: String,
+        all: &str,
+        property: &str,
+    ) {
+        if let [a, b] = members.as_slice() {
+            self.add(a, predicate.clone(), b.clone());
+            self.reify(annotations, a, &predicate, b);
+        } else {
+            let node = self.blank();
+            self.add(
+                &node,
+                format!("{}type", RDF),
+                Term::Iri(format!("{}{}", OWL, all)),
+            );
+            let list = self.list(members);
+            self.add(&node, format!("{}{}", OWL, property), list);
+            self.assertions(&node, annotations);
+        }
+    }
+
+    // annotates the triple of an axiom through an `owl:Axiom` node
+    fn reify(
+        &mut self,
+        annotations: &[Annotation],
+        subject: &Term,
+        predicate: &str,
+        object: &Term,
+    ) {
+        if annotations.is_empty() {
+            return;
+        }
+
+        let node = self.blank();
+        self.add(
+            &node,
+            format!("{}type", RDF),
+            Term::Iri(format!("{}Axiom", OWL)),
+        );
+        self.add(&node, format!("{}annotatedSource", OWL), subject.clone());
+        self.add(
+            &node,
+            format!("{}annotatedProperty", OWL),
+            Term::Iri(predicate.to_string()),
+        );
+        self.add(&node, format!("{}annotatedTarget", OWL), object.clone());
+        self.assertions(&node, annotations);
+    }
+
+    fn assertions(&mut self, subject: &Term, annotations: &[Annotation]) {
+        for annotation in annotations {
+            let property = self.annotation_property(annotation.property.index);
+
+            let value = match &annotation.value {
+                AnnotationValue::Literal(literal, Some(language)) => Term::Literal(
+                    self.id(literal.index).to_string(),
+                    Tag::Language(language.clone()),
+                ),
+                AnnotationValue::Literal(literal, None) => {
+                    self.literal(self.id(literal.index), Datatype::String)
+                }
+                AnnotationValue::Iri(iri) => Term::Iri(iri.clone()),
+            };
+
+            self.add(subject, property, value);
+        }
+    }
+
+    fn literal(&self, lexical: &str, datatype: Datatype) -> Term {
+        Term::Literal(
+            lexical.to_string(),
+            Tag::Datatype(format!("{}{}", XSD, datatype.name())),
+        )
+    }
+
+    // an RDF list of the terms, `rdf:nil` if there are none
+    fn list(&mut self, terms: Vec<Term>) -> Term {
+        let mut list = Term::Iri(format!("{}nil", RDF));
+
+        for term in terms.into_iter().rev() {
+            let node = self.blank();
+            self.add(&node, format!("{}first", RDF), term);
+            self.add(&node, format!("{}rest", RDF), list);
+            list = node;
+        }
+
+        list
+    }
+
+    // a blank node of type `owl:Class` with the list of operands as the
+    // object of `predicate`
+    fn combination(&mut self, predicate: &str, operands: Vec<Term>) -> Term {
+        let node = self.blank();
+        self.add(
+            &node,
+            format!("{}type", RDF),
+            Term::Iri(format!("{}Class", OWL)),
+        );
+        let list = self.list(operands);
+        self.add(&node, format!("{}{}", OWL, predicate), list);
+        node
+    }
+
+    fn class(&mut self, class: &Class) -> Term {
+        match class {
+            Class::Identifier(class) => self.class_iri(class.index),
+            Class::Defined(class, properties) => self.intersection(class.index, properties),
+            Class::Enumerated(individuals) => self.one_of(individuals.iter().map(|i| i.index)),
+            Class::Union(classes) => self.union(classes.iter().map(|c| c.index)),
+            Class::Complement(class) => {
+                let node = self.blank();
+                self.add(
+                    &node,
+                    format!("{}type", RDF),
+                    Term::Iri(format!("{}Class", OWL)),
+                );
+                let complement = self.class(class);
+                self.add(&node, format!("{}complementOf", OWL), complement);
+                node
+            }
+        }
+    }
+
+    fn intersection(&mut self, class: usize, properties: &[Located<Property>]) -> Term {
+        let mut operands = vec![self.class_iri(class)];
+
+        for property in properties {
+            operands.push(self.restriction(&property.value));
+        }
+
+        if operands.len() == 1 {
+            operands.remove(0)
+        } else {
+            self.combination("intersectionOf", operands)
+        }
+    }
+
+    fn union(&mut self, classes: impl Iterator<Item = usize>) -> Term {
+        let classes = classes.map(|c| self.class_iri(c)).collect();
+        self.combination("unionOf", classes)
+    }
+
+    fn one_of(&mut self, individuals: impl Iterator<Item = usize>) -> Term {
+        let individuals = individuals.map(|i| self.individual(i)).collect();
+        self.combination("oneOf", individuals)
+    }
+
+    fn restriction(&mut self, property: &Property) -> Term {
+        let node = self.blank();
+        self.add(
+            &node,
+            format!("{}type", RDF),
+            Term::Iri(format!("{}Restriction", OWL)),
+        );
+
+        // (predicate, object) of the filler, and of the qualification of
+        // cardinality restrictions
+        let (p, filler, qualification) = match &property.description {
+            PropertyDescription::Object(description) => {
+                let p = self.object_property(property.index);
+
+                match description {
+                    ObjectDescription::Some(class) => {
+                        (p, ("someValuesFrom", self.class(class)), None)
+                    }
+                    ObjectDescription::Only(class) => {
+                        (p, ("allValuesFrom", self.class(class)), None)
+                    }
+                    ObjectDescription::Value(individual) => {
+                        (p, ("hasValue", self.individual(individual.index)), None)
+                    }
+                    ObjectDescription::Min(n, class) => {
+                        let cardinality = self.cardinality(*n);
+                        let class = self.class(class);
+                        (
+                            p,
+                            ("minQualifiedCardinality", cardinality),
+                            Some(("onClass", class)),
+                        )
+                    }
+                    ObjectDescription::Max(n, class) => {
+                        let cardinality = self.cardinality(*n);
+                        let class = self.class(class);
+                        (
+                            p,
+                            ("maxQualifiedCardinality", cardinality),
+                            Some(("onClass", class)),
+                        )
+                    }
+                    ObjectDescription::Exactly(n, class) => {
+                        let cardinality = self.cardinality(*n);
+                        let class = self.class(class);
+                        (
+                            p,
+                            ("qualifiedCardinality", cardinality),
+                            Some(("onClass", class)),
+                        )
+                    }
+                }
+            }
+            PropertyDescription::Data(description) => {
+                let p = self.data_property(property.index);
+
+                match description {
+                    DataDescription::Some(data) => (p, ("someValuesFrom", self.data(data)), None),
+                    DataDescription::Only(data) => (p, ("allValuesFrom", self.data(data)), None),
+                    DataDescription::Value(literal) => {
+                        let literal = self.literal(
+                            self.id(literal.value.index),
+                            functional::value_datatype(&self.datatypes, property.index),
+                        );
+                        (p, ("hasValue", literal), None)
+                    }
+                    DataDescription::Min(n, data) => {
+                        let cardinality = self.cardinality(*n);
+                        let data = self.data(data);
+                        (
+                            p,
+                            ("minQualifiedCardinality", cardinality),
+                            Some(("onDataRange", data)),
+                        )
+                    }
+                    DataDescription::Max(n, data) => {
+                        let cardinality = self.cardinality(*n);
+                        let data = self.data(data);
+                        (
+                            p,
+                            ("maxQualifiedCardinality", cardinality),
+                            Some(("onDataRange", data)),
+                        )
+                    }
+                    DataDescription::Exactly(n, data) => {
+                        let cardinality = self.cardinality(*n);
+                        let data = self.data(data);
+                        (
+                            p,
+                            ("qualifiedCardinality", cardinality),
+                            Some(("onDataRange", data)),
+                        )
+                    }
+                }
+            }
+        };
+
+        self.add(&node, format!("{}onProperty", OWL), p);
+        self.add(&node, format!("{}{}", OWL, filler.0), filler.1);
+
+        if let Some((predicate, object)) = qualification {
+            self.add(&node, format!("{}{}", OWL, predicate), object);
+        }
+
+        node
+    }
+
+    fn cardinality(&self, n: usize) -> Term {
+        Term::Literal(
+            n.to_string(),
+            Tag::Datatype(format!("{}nonNegativeInteger", XSD)),
+        )
+    }
+
+    fn data(&mut self, data: &Data) -> Term {
+        let datatype = Term::Iri(format!("{}{}", XSD, data.datatype.name()));
+
+        match &data.restriction {
+            Some(restriction) => {
+                let node = self.blank();
+                self.add(
+                    &node,
+                    format!("{}type", RDF),
+                    Term::Iri(format!("{}Datatype", RDFS)),
+                );
+                self.add(&node, format!("{}onDatatype", OWL), datatype);
+
+                let facet = self.blank();
+                let value = self.literal(self.id(restriction.1.index), data.datatype);
+                self.add(&facet, format!("{}{}", XSD, restriction.0.facet()), value);
+
+                let list = self.list(vec![facet]);
+                self.add(&node, format!("{}withRestrictions", OWL), list);
+                node
+            }
+            None => datatype,
+        }
+    }
+}
@@ -0,0 +1,540 @@
+use std::collections::{BTreeSet, HashMap};
+
+use crate::{
+    hierarchy::{self, Node},
+    parser::{Class, ClassDecl, Frame, ObjectDescription, Ontology, Property, PropertyDescription},
+    span::{Located, SourceMap},
+    table::SymbolTable,
+};
+
+/// Concept of the EL description logic, built from intersections and
+/// existential restrictions only.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Concept {
+    Top,
+    Bottom,
+    Named(Node),
+    And(Vec<Concept>),
+    /// `∃r.C`, with the property by its index in the symbol table.
+    Some(usize, Box<Concept>),
+}
+
+// concepts inside the reasoner, names and the ones introduced by
+// normalization alike
+type Id = usize;
+
+const TOP: Id = 0;
+const BOTTOM: Id = 1;
+
+/// Axioms in the normal form of the completion rules.
+#[derive(Default)]
+struct Builder {
+    nodes: HashMap<Node, Id>,
+    names: Vec<Option<Node>>,
+    /// `A1 ⊓ ... ⊓ An ⊑ B`
+    conjunctions: Vec<(Vec<Id>, Id)>,
+    /// `A ⊑ ∃r.B`
+    existentials: Vec<(Id, usize, Id)>,
+    /// `∃r.A ⊑ B`
+    restrictions: Vec<(usize, Id, Id)>,
+    /// `r1 ∘ ... ∘ rn ⊑ s`
+    chains: Vec<(Vec<usize>, usize)>,
+}
+
+/// Inferred subsumption hierarchy of an ontology.
+pub struct Classification {
+    names: Vec<Option<Node>>,
+    ids: HashMap<Node, Id>,
+    subsumers: Vec<BTreeSet<Id>>,
+}
+
+/// Classifies the named classes and the classes described by declarations
+/// with the EL completion rules.
+///
+/// Constructs outside EL, such as `ONLY`, `MAX` and unions, are dropped
+/// where that only weakens an axiom, and axioms that would be strengthened
+/// by dropping them are left out, so every inferred subsumption holds but
+/// some may be missed.
+pub fn classify<'o>(ontologies: impl IntoIterator<Item = &'o Ontology>) -> Classification {
+    let mut builder = Builder::new();
+
+    for ontology in ontologies {
+        for frame in &ontology.frames {
+            builder.frame(frame);
+        }
+    }
+
+    builder.saturate()
+}
+
+impl Builder {
+    fn new() -> Self {
+        Self {
+            names: vec![None, None],
+            ..Self::default()
+        }
+    }
+
+    fn fresh(&mut self) -> Id {
+        self.names.push(None);
+        self.names.len() - 1
+    }
+
+    fn id(&mut self, node: Node) -> Id {
+        if let Some(&id) = self.nodes.get(&node) {
+            return id;
+        }
+
+        let id = self.fresh();
+        self.names[id] = Some(node);
+        self.nodes.insert(node, id);
+        id
+    }
+
+    fn frame(&mut self, frame: &Located<Frame>) {
+        match &frame.value {
+            Frame::Decl { decl, .. } => {
+                let node = Concept::Named(Node::Anonymous(decl.span));
+
+                match &decl.value {
+                    ClassDecl::Defined(super_class, properties) => {
+                        let (concept, exact) = intersection(super_class.index, properties);
+                        self.subsume(&node, &concept);
+
+                        if exact {
+                            self.subsume(&concept, &node);
+                        }
+                    }
+                    ClassDecl::Primitive(super_class, properties) => {
+                        let (concept, _) = intersection(super_class.index, properties);
+                        self.subsume(&node, &concept);
+                    }
+                    ClassDecl::Union(classes) => {
+                        for class in classes {
+                            self.subsume(&Concept::Named(Node::Named(class.index)), &node);
+                        }
+                    }
+                    ClassDecl::Enumerated(_) => {}
+                }
+            }
+            Frame::Class {
+                class,
+                super_classes,
+                ..
+            } => {
+                let class = Concept::Named(Node::Named(class.index));
+
+                for super_class in super_classes {
+                    let (super_class, _) = concept(&super_class.value);
+                    self.subsume(&class, &super_class);
+                }
+            }
+            Frame::EquivalentClasses(misc) => {
+                let members = misc.members.iter().map(concept).collect::<Vec<_>>();
+
+                for (i, (sub, exact)) in members.iter().enumerate() {
+                    for (j, (sup, _)) in members.iter().enumerate() {
+                        if i != j && *exact {
+                            self.subsume(sub, sup);
+                        }
+                    }
+                }
+            }
+            Frame::DisjointClasses(misc) => {
+                let members = misc.members.iter().map(concept).collect::<Vec<_>>();
+
+                for (i, (a, a_exact)) in members.iter().enumerate() {
+                    for (b, b_exact) in &members[i + 1..] {
+                        if *a_exact && *b_exact {
+                            let both = Concept::And(vec![a.clone(), b.clone()]);
+                            self.subsume(&both, &Concept::Bottom);
+                        }
+                    }
+                }
+            }
+            Frame::ObjectProperty {
+                property, chains, ..
+            } => {
+                for chain in chains {
+                    let roles = chain.0.iter().map(|role| role.index).collect();
+                    self.chains.push((roles, property.index));
+                }
+            }
+            Frame::AnnotationProperty { .. }
+            | Frame::DataProperty { .. }
+            | Frame::DisjointProperties(_)
+            | Frame::SameIndividual(_)
+            | Frame::DifferentIndividuals(_) => {}
+        }
+    }
+
+    /// Normalizes `sub ⊑ sup`.
+    fn subsume(&mut self, sub: &Concept, sup: &Concept) {
+        let conjuncts = self.conjuncts(sub);
+
+        let sub = match conjuncts[..] {
+            [sub] => sub,
+            _ => {
+                let sub = self.fresh();
+                self.conjunctions.push((conjuncts, sub));
+                sub
+            }
+        };
+
+        self.superclass(sub, sup);
+    }
+
+    // atoms whose intersection is below `concept`
+    fn conjuncts(&mut self, concept: &Concept) -> Vec<Id> {
+        match concept {
+            Concept::And(concepts) => concepts.iter().flat_map(|c| self.conjuncts(c)).collect(),
+            _ => vec![self.subclass(concept)],
+        }
+    }
+
+    // an atom `concept` is below
+    fn subclass(&mut self, concept: &Concept) -> Id {
+        match concept {
+            Concept::Top => TOP,
+            Concept::Bottom => BOTTOM,
+            Concept::Named(node) => self.id(*node),
+            Concept::And(_) => {
+                let conjuncts = self.conjuncts(concept);
+                let atom = self.fresh();
+                self.conjunctions.push((conjuncts, atom));
+                atom
+            }
+            Concept::Some(role, filler) => {
+                let filler = self.subclass(filler);
+                let atom = self.fresh();
+                self.restrictions.push((*role, filler, atom));
+                atom
+            }
+        }
+    }
+
+    // adds `sub ⊑ concept` for an atom `sub`
+    fn superclass(&mut self, sub: Id, concept: &Concept) {
+        match concept {
+            Concept::Top => {}
+            Concept::Bottom => self.conjunctions.push((vec![sub], BOTTOM)),
+            Concept::Named(node) => {
+                let sup = self.id(*node);
+                self.conjunctions.push((vec![sub], sup));
+            }
+            Concept::And(concepts) => {
+                for concept in concepts {
+                    self.superclass(sub, concept);
+                }
+            }
+            Concept::Some(role, filler) => {
+                let filler = match filler.as_ref() {
+                    Concept::Top => TOP,
+                    Concept::Bottom => BOTTOM,
+                    Concept::Named(node) => self.id(*node),
+                    _ => {
+                        let atom = self.fresh();
+                        self.superclass(atom, filler);
+                        atom
+                    }
+                };
+
+                self.existentials.push((sub, *role, filler));
+            }
+        }
+    }
+
+    fn saturate(self) -> Classification {
+        let count = self.names.len();
+        let mut subsumers = (0..count)
+            .map(|id| BTreeSet::from([id, TOP]))
+            .collect::<Vec<_>>();
+        let mut relations: HashMap<usize, BTreeSet<(Id, Id)>> = HashMap::new();
+
+        let mut changed = true;
+
+        while changed {
+            changed = false;
+
+            for (x, atoms) in subsumers.iter_mut().enumerate() {
+                for (conjuncts, sup) in &self.conjunctions {
+                    if conjuncts.iter().all(|a| atoms.contains(a)) {
+                        changed |= atoms.insert(*sup);
+                    }
+                }
+
+                for (sub, role, filler) in &self.existentials {
+                    if atoms.contains(sub) {
+                        changed |= relations.entry(*role).or_default().insert((x, *filler));
+                    }
+                }
+            }
+
+            for (role, filler, sup) in &self.restrictions {
+                for &(x, y) in relations.get(role).into_iter().flatten() {
+                    if subsumers[y].contains(filler) && !subsumers[x].contains(sup) {
+                        subsumers[x].insert(*sup);
+                        changed = true;
+                    }
+                }
+            }
+
+            // a successor that cannot exist makes its predecessor unsatisfiable
+            for &(x, y) in relations.values().flatten() {
+                if subsumers[y].contains(&BOTTOM) && !subsumers[x].contains(&BOTTOM) {
+                    subsumers[x].insert(BOTTOM);
+                    changed = true;
+                }
+            }
+
+            for (roles, sup) in &self.chains {
+                let mut pairs = relations.get(&roles[0]).cloned().unwrap_or_default();
+
+                for role in &roles[1..] {
+                    let next = relations.get(role).cloned().unwrap_or_default();
+                    pairs = pairs
+                        .iter()
+                        .flat_map(|&(x, y)| {
+                            next.iter()
+                                .filter(move |(z, _)| *z == y)
+                                .map(move |&(_, w)| (x, w))
+                        })
+                        .collect();
+                }
+
+                let relation = relations.entry(*sup).or_default();
+                for pair in pairs {
+                    changed |= relation.insert(pair);
+                }
+            }
+        }
+
+        Classification {
+            names: self.names,
+            ids: self.nodes,
+            subsumers,
+        }
+    }
+}
+
+/// An EL concept for a class description, and whether it is exact rather
+/// than a weakening of the description.
+pub fn concept(class: &Class) -> (Concept, bool) {
+    match class {
+        Class::Identifier(class) => (Concept::Named(Node::Named(class.index)), true),
+        Class::Defined(super_class, properties) => intersection(super_class.index, properties),
+        Class::Enumerated(_) | Class::Union(_) | Class::Complement(_) => (Concept::Top, false),
+    }
+}
+
+fn intersection(super_class: usize, properties: &[Located<Property>]) -> (Concept, bool) {
+    let mut exact = true;
+    let mut concepts = vec![Concept::Named(Node::Named(super_class))];
+
+    for property in properties {
+        let (concept, restriction_exact) = restriction(&property.value);
+        exact &= restriction_exact;
+
+        if concept != Concept::Top {
+            concepts.push(concept);
+        }
+    }
+
+    (Concept::And(concepts), exact)
+}
+
+fn restriction(property: &Property) -> (Concept, bool) {
+    let some = |class: &Class, exact: bool| {
+        let (filler, filler_exact) = concept(class);
+        (
+            Concept::Some(property.index, Box::new(filler)),
+            exact && filler_exact,
+        )
+    };
+
+    match &property.description {
+        PropertyDescription::Object(description) => match description {
+            ObjectDescription::Some(class) => some(class, true),
+            // at least one is the same as some, more than one implies it
+            ObjectDescription::Min(1, class) => some(class, true),
+            ObjectDescription::Min(1.., class) | ObjectDescription::Exactly(1.., class) => {
+                some(class, false)
+            }
+            ObjectDescription::Only(_)
+            | ObjectDescription::Value(_)
+            | ObjectDescription::Min(..)
+            | ObjectDescription::Max(..)
+            | ObjectDescription::Exactly(..) => (Concept::Top, false),
+        },
+        PropertyDescription::Data(_) => (Concept::Top, false),
+    }
+}
+
+impl Classification {
+    fn id(&self, node: Node) -> Option<Id> {
+        self.ids.get(&node).copied()
+    }
+
+    fn nodes(&self) -> impl Iterator<Item = (Id, Node)> + '_ {
+        self.names
+            .iter()
+            .enumerate()
+            .filter_map(|(id, node)| node.map(|node| (id, node)))
+    }
+
+    /// Whether `sub` is inferred to be a subclass of `sup`.
+    pub fn subsumes(&self, sup: Node, sub: Node) -> bool {
+        match (self.id(sup), self.id(sub)) {
+            (Some(sup), Some(sub)) => {
+                self.subsumers[sub].contains(&sup) || self.subsumers[sub].contains(&BOTTOM)
+            }
+            _ => sup == sub,
+        }
+    }
+
+    pub fn is_satisfiable(&self, node: Node) -> bool {
+        self.id(node)
+            .is_none_or(|id| !self.subsumers[id].contains(&BOTTOM))
+    }
+
+    /// Classes inferred to be empty.
+    pub fn unsatisfiable(&self) -> Vec<Node> {
+        self.nodes()
+            .filter(|(id, _)| self.subsumers[*id].contains(&BOTTOM))
+            .map(|(_, node)| node)
+            .collect()
+    }
+
+    /// Classes inferred to be equivalent to `node`, other than itself.
+    pub fn equivalents(&self, node: Node) -> Vec<Node> {
+        self.nodes()
+            .map(|(_, other)| other)
+            .filter(|other| {
+                *other != node && self.subsumes(*other, node) && self.subsumes(node, *other)
+            })
+            .collect()
+    }
+
+    /// Every inferred superclass of `node`, other than itself and its
+    /// equivalents.
+    pub fn superclasses(&self, node: Node) -> Vec<Node> {
+        self.nodes()
+            .map(|(_, other)| other)
+            .filter(|other| self.subsumes(*other, node) && !self.subsumes(node, *other))
+            .collect()
+    }
+
+    /// The most specific inferred superclasses of `node`, one of each set of
+    /// equivalent classes.
+    pub fn parents(&self, node: Node) -> Vec<Node> {
+        let superclasses = self.superclasses(node);
+
+        superclasses
+            .iter()
+            .copied()
+            .filter(|parent| {
+                !superclasses.iter().any(|other| {
+                    other != parent
+                        && self.subsumes(*parent, *other)
+                        && !self.subsumes(*other, *parent)
+                })
+            })
+            .filter(|parent| self.representative(*parent) == *parent)
+            .collect()
+    }
+
+    // the first of a set of equivalent classes
+    fn representative(&self, node: Node) -> Node {
+        self.nodes()
+            .map(|(_, other)| other)
+            .find(|other| self.subsumes(*other, node) && self.subsumes(node, *other))
+            .unwrap_or(node)
+    }
+
+    /// The inferred hierarchy as an indented tree under `Thing`, with
+    /// equivalent classes on the same line and unsatisfiable classes under
+    /// `Nothing`.
+    pub fn render(&self, map: &SourceMap, table: &SymbolTable) -> String {
+        let mut tree = "Thing\n".to_string();
+
+        let satisfiable = self
+            .nodes()
+            .map(|(_, node)| node)
+            .filter(|node| self.is_satisfiable(*node) && self.representative(*node) == *node)
+            .collect::<Vec<_>>();
+
+        for node in &satisfiable {
+            if self.parents(*node).is_empty() {
+                self.render_node(*node, 1, &satisfiable, map, table, &mut tree);
+            }
+        }
+
+        let unsatisfiable = self.unsatisfiable();
+
+        if !unsatisfiable.is_empty() {
+            tree.push_str("Nothing\n");
+
+            for node in unsatisfiable {
+                tree.push_str(&format!("  {}\n", hierarchy::name(node, map, table)));
+            }
+        }
+
+        tree
+    }
+
+    fn render_node(
+        &self,
+        node: Node,
+        depth: usize,
+        nodes: &[Node],
+        map: &SourceMap,
+        table: &SymbolTable,
+        tree: &mut String,
+    ) {
+        let names = std::iter::once(node)
+            .chain(self.equivalents(node))
+            .map(|node| hierarchy::name(node, map, table))
+            .collect::<Vec<_>>();
+
+        tree.push_str(&format!("{:1$}{2}\n", "", depth * 2, names.join(" = ")));
+
+        for child in nodes {
+            if self.parents(*child).contains(&node) {
+                self.render_node(*child, depth + 1, nodes, map, table, tree);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{lexer, parser, table::Type};
+
+    #[test]
+    fn infers_a_subsumption_through_an_existential() {
+        let text = "EquivalentClasses: CheesyPizza, (Pizza AND (hasTopping SOME Cheese))\n\
+                    Class: Mozzarella SubClassOf: Cheese\n\
+                    Class: Margherita SubClassOf: (Pizza AND (hasTopping SOME Mozzarella))\n";
+
+        let mut map = SourceMap::new();
+        let mut table = SymbolTable::new();
+        let file_id = map.add("test.txt".to_string(), text.to_string());
+        let Ok(tokens) = lexer::parse(&map, file_id, &mut table) else {
+            panic!("could not lex {:?}", text);
+        };
+        let Ok(ontology) = parser::parse(&tokens, &mut table) else {
+            panic!("could not parse {:?}", text);
+        };
+
+        let mut class = |name: &str| {
+            let index = table.get_or_insert(Type::Class, name.to_string());
+            Node::Named(index)
+        };
+        let (cheesy, margherita) = (class("CheesyPizza"), class("Margherita"));
+        let classification = classify([&ontology]);
+
+        assert!(classification.subsumes(cheesy, margherita));
+        assert!(!classification.subsumes(margherita, cheesy));
+    }
+}
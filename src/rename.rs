@@ -0,0 +1,238 @@
+use crate::{
+    cst::SyntaxNode,
+    lexer::{self, Token},
+    span::{Located, SourceMap},
+    table::{Symbol, SymbolTable, Type},
+};
+
+#[derive(Debug, Clone)]
+pub enum RenameErr {
+    /// No class, individual or property has the old name.
+    Unknown(String),
+    /// The new name is already the id of another class, individual or
+    /// property, which would merge the two in the table.
+    Collision(String),
+    /// The new name would not be lexed as an identifier of the same kind.
+    InvalidName { name: String, expected: Type },
+}
+
+impl RenameErr {
+    pub fn describe(&self) -> String {
+        match self {
+            Self::Unknown(name) => {
+                format!("'{}' is not a class, individual or property", name)
+            }
+            Self::Collision(name) => format!("'{}' is already used", name),
+            Self::InvalidName {
+                name,
+                expected: Type::Class | Type::Individual,
+            } => format!(
+                "'{}' is not a valid class name: it must start with an uppercase letter \
+                 and contain only letters and '_'",
+                name
+            ),
+            Self::InvalidName { name, .. } => format!(
+                "'{}' is not a valid property name: it must be 'has...' or 'is...Of'",
+                name
+            ),
+        }
+    }
+}
+
+/// Finds the class, individual or property named `old` and checks that it can be
+/// renamed to `new`, returning its index in the table.
+pub fn check(table: &SymbolTable, old: &str, new: &str) -> Result<usize, RenameErr> {
+    // literals share the table but not the namespace of identifiers
+    let named = |symbol: &Symbol, id: &str| {
+        symbol.id() == id
+            && matches!(
+                symbol.type_(),
+                Type::Class | Type::Individual | Type::Property(_)
+            )
+    };
+
+    let (&index, symbol) = table
+        .symbols()
+        .iter()
+        .find(|(_, symbol)| named(symbol, old))
+        .ok_or_else(|| RenameErr::Unknown(old.to_string()))?;
+
+    if table.symbols().values().any(|symbol| named(symbol, new)) {
+        return Err(RenameErr::Collision(new.to_string()));
+    }
+
+    // the new name alone must be lexed as a single identifier of the same
+    // kind, so neither a keyword nor a datatype takes its place; identifiers
+    // must be followed by something, hence the trailing space
+    let mut map = SourceMap::new();
+    let file_id = map.add(String::new(), format!("{} ", new));
+
+    let valid = match lexer::parse(&map, file_id, &mut SymbolTable::new()).as_deref() {
+        Ok([token]) => {
+            token.span.end() == new.len()
+                && matches!(
+                    (&token.value, symbol.type_()),
+                    (
+                        Token::ClassIdentifier { .. },
+                        Type::Class | Type::Individual
+                    ) | (Token::PropertyIdentifier { .. }, Type::Property(_))
+                )
+        }
+        _ => false,
+    };
+
+    if valid {
+        Ok(index)
+    } else {
+        Err(RenameErr::InvalidName {
+            name: new.to_string(),
+            expected: symbol.type_(),
+        })
+    }
+}
+
+/// Replaces every identifier token of the symbol at `index` in the tree of a
+/// file with `new`, returning the renamed tree and the number of occurrences
+/// replaced.
+///
+/// Only class and property identifiers are replaced, so literals and
+/// annotations with the same text as the old name are kept.
+pub fn apply(
+    root: &SyntaxNode,
+    tokens: &[Located<Token>],
+    index: usize,
+    new: &str,
+) -> (SyntaxNode, usize) {
+    // last occurrence first, so replacing one does not move the offsets of
+    // the ones still to be replaced
+    let starts = tokens
+        .iter()
+        .rev()
+        .filter_map(|token| match token.value {
+            Token::ClassIdentifier { index: i } | Token::PropertyIdentifier { index: i }
+                if i == index =>
+            {
+                Some(token.span.start())
+            }
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+
+    let mut root = root.clone();
+
+    for &start in &starts {
+        let token = root
+            .tokens()
+            .into_iter()
+            .find(|token| !token.is_trivia() && token.range().start == start)
+            .expect("every token of the file is in its tree");
+
+        root = SyntaxNode::new_root(token.replace_with(new));
+    }
+
+    (root, starts.len())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{cst, parser};
+
+    // renames `old` to `new` in a single file
+    fn rename(text: &str, old: &str, new: &str) -> Result<(String, usize), RenameErr> {
+        let mut map = SourceMap::new();
+        let mut table = SymbolTable::new();
+        let file_id = map.add("test.txt".to_string(), text.to_string());
+
+        let Ok(tokens) = lexer::parse(&map, file_id, &mut table) else {
+            panic!("could not lex {:?}", text);
+        };
+        let Ok(ontology) = parser::parse(&tokens, &mut table) else {
+            panic!("could not parse {:?}", text);
+        };
+
+        let index = check(&table, old, new)?;
+        let (root, count) = apply(&cst::build(text, &tokens, &ontology), &tokens, index, new);
+        Ok((root.text(), count))
+    }
+
+    #[test]
+    fn replaces_only_the_identifiers() {
+        let text = "Pizza AND (hasTopping SOME  Cheese)\nCheese AND (hasName VALUE \"Cheese\")\n";
+
+        let (renamed, count) = rename(text, "Cheese", "Mozzarella").unwrap();
+
+        assert_eq!(count, 2);
+        assert_eq!(
+            renamed,
+            "Pizza AND (hasTopping SOME  Mozzarella)\nMozzarella AND (hasName VALUE \"Cheese\")\n"
+        );
+    }
+
+    #[test]
+    fn renames_properties() {
+        let text = "Pizza AND (hasTopping SOME Cheese) AND (hasTopping MIN 2 Cheese)\n";
+
+        let (renamed, count) = rename(text, "hasTopping", "hasIngredient").unwrap();
+
+        assert_eq!(count, 2);
+        assert_eq!(
+            renamed,
+            "Pizza AND (hasIngredient SOME Cheese) AND (hasIngredient MIN 2 Cheese)\n"
+        );
+    }
+
+    #[test]
+    fn refuses_a_name_already_used() {
+        let text = "Pizza AND (hasTopping SOME Cheese) AND (hasBase SOME Dough)\n";
+
+        for (old, new) in [
+            ("Cheese", "Dough"),
+            ("Cheese", "Pizza"),
+            ("hasTopping", "hasBase"),
+        ] {
+            assert!(matches!(
+                rename(text, old, new),
+                Err(RenameErr::Collision(name)) if name == new
+            ));
+        }
+    }
+
+    #[test]
+    fn accepts_a_name_used_only_by_a_literal() {
+        let text =
+            "Pizza AND (hasTopping SOME Cheese)\nCheese AND (hasName VALUE \"Mozzarella\")\n";
+
+        let (renamed, count) = rename(text, "Cheese", "Mozzarella").unwrap();
+
+        assert_eq!(count, 2);
+        assert_eq!(
+            renamed,
+            "Pizza AND (hasTopping SOME Mozzarella)\nMozzarella AND (hasName VALUE \"Mozzarella\")\n"
+        );
+    }
+
+    #[test]
+    fn refuses_unknown_and_invalid_names() {
+        let text = "Pizza AND (hasTopping SOME Cheese)\n";
+
+        assert!(matches!(
+            rename(text, "Dough", "Base"),
+            Err(RenameErr::Unknown(_))
+        ));
+        assert!(matches!(
+            rename(text, "Cheese", "hasCheese"),
+            Err(RenameErr::InvalidName {
+                expected: Type::Class,
+                ..
+            })
+        ));
+        assert!(matches!(
+            rename(text, "hasTopping", "Topping"),
+            Err(RenameErr::InvalidName {
+                expected: Type::Property(_),
+                ..
+            })
+        ));
+    }
+}
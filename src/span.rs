@@ -1,59 +1,152 @@
+/// Unit in which column numbers are reported.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ColumnUnit {
+    /// Unicode scalar values, as a human would count them.
+    #[default]
+    Char,
+    /// UTF-16 code units, as expected by most editors and LSP clients.
+    Utf16,
+}
+
 #[derive(Debug, Clone)]
 pub struct Source {
     pub filename: String,
     pub content: String,
+    line_starts: Vec<usize>,
 }
 
-#[derive(Clone, Copy)]
-pub struct Span<'s> {
-    start: usize,
-    end: usize,
-    src: &'s Source,
+impl Source {
+    pub fn new(filename: String, content: String) -> Self {
+        // byte offset of the first character of every line, used to find
+        // the line of an offset with a binary search instead of a rescan
+
+        let line_starts = std::iter::once(0)
+            .chain(content.match_indices('\n').map(|(i, _)| i + 1))
+            .collect();
+
+        Self {
+            filename,
+            content,
+            line_starts,
+        }
+    }
+
+    fn line_index(&self, offset: usize) -> usize {
+        self.line_starts.partition_point(|&start| start <= offset) - 1
+    }
+
+    fn position(&self, offset: usize, column_unit: ColumnUnit) -> (usize, usize) {
+        // line and column numbers are 1-indexed
+
+        let line = self.line_index(offset);
+        let before = &self.content[self.line_starts[line]..offset];
+
+        let column = match column_unit {
+            ColumnUnit::Char => before.chars().count(),
+            ColumnUnit::Utf16 => before.encode_utf16().count(),
+        };
+
+        (line + 1, column + 1)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct FileId(u32);
+
+/// Owns every loaded file, so spans can refer to them by id.
+#[derive(Debug, Default)]
+pub struct SourceMap {
+    files: Vec<Source>,
+    column_unit: ColumnUnit,
 }
 
-impl<'s> Span<'s> {
-    pub fn fragment(&self) -> &'s str {
-        &self.src.content[self.start..self.end]
+impl SourceMap {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_column_unit(self, column_unit: ColumnUnit) -> Self {
+        Self {
+            column_unit,
+            ..self
+        }
+    }
+
+    pub fn add(&mut self, filename: String, content: String) -> FileId {
+        let id = FileId(self.files.len() as u32);
+        self.files.push(Source::new(filename, content));
+        id
     }
 
-    pub fn line(&self) -> usize {
-        // line numbers are 1-indexed
-        // get the number of newlines before the start of the span
+    pub fn get(&self, file_id: FileId) -> &Source {
+        &self.files[file_id.0 as usize]
+    }
 
-        self.src.content[..self.start].matches('\n').count() + 1
+    pub fn span(&self, file_id: FileId) -> Span {
+        Span {
+            file_id,
+            start: 0,
+            end: self.get(file_id).content.len() as u32,
+        }
     }
 
-    pub fn column(&self) -> usize {
-        // column numbers are 1-indexed
-        // get the number of characters between the last newline and the start of the span
+    pub fn fragment(&self, span: Span) -> &str {
+        &self.get(span.file_id).content[span.start as usize..span.end as usize]
+    }
 
-        let last_newline = self.src.content[..self.start]
-            .rfind('\n')
-            .map(|i| i + 1)
-            .unwrap_or(0);
+    pub fn line(&self, span: Span) -> usize {
+        self.position(span.file_id, span.start).0
+    }
+
+    pub fn column(&self, span: Span) -> usize {
+        self.position(span.file_id, span.start).1
+    }
+
+    pub fn location(&self, span: Span) -> String {
+        let filename = &self.get(span.file_id).filename;
+        format!("{}:{}:{}", filename, self.line(span), self.column(span))
+    }
 
-        self.start - last_newline + 1
+    pub fn end_location(&self, span: Span) -> String {
+        let filename = &self.get(span.file_id).filename;
+        let (line, column) = self.position(span.file_id, span.end);
+        format!("{}:{}:{}", filename, line, column)
     }
 
-    pub fn location(&self) -> String {
-        format!("{}:{}:{}", self.src.filename, self.line(), self.column())
+    fn position(&self, file_id: FileId, offset: u32) -> (usize, usize) {
+        self.get(file_id)
+            .position(offset as usize, self.column_unit)
     }
 }
 
-impl Span<'_> {
+/// Byte range inside one of the files of a `SourceMap`.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Span {
+    file_id: FileId,
+    start: u32,
+    end: u32,
+}
+
+impl Span {
+    pub fn start(&self) -> usize {
+        self.start as usize
+    }
+
+    pub fn end(&self) -> usize {
+        self.end as usize
+    }
+
     pub fn take(&self, n: usize) -> Self {
         Self {
-            start: self.start,
-            end: self.start + n,
-            src: self.src,
+            end: self.start + n as u32,
+            ..*self
         }
     }
 
     pub fn shift(&self, n: usize) -> Self {
         Self {
-            start: self.start + n,
-            end: self.end,
-            src: self.src,
+            start: self.start + n as u32,
+            ..*self
         }
     }
 
@@ -65,40 +158,36 @@ impl Span<'_> {
 
     pub fn merge(&self, other: &Self) -> Self {
         Self {
-            start: self.start,
             end: other.end,
-            src: self.src,
+            ..*self
         }
     }
 }
 
-impl<'s> From<&'s Source> for Span<'s> {
-    fn from(src: &'s Source) -> Self {
-        Self {
-            start: 0,
-            end: src.content.len(),
-            src,
-        }
+impl std::fmt::Debug for Span {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}:{}..{}", self.file_id.0, self.start, self.end)
     }
 }
 
-pub struct Located<'s, T> {
+#[derive(Clone)]
+pub struct Located<T> {
     pub value: T,
-    pub span: Span<'s>,
+    pub span: Span,
 }
 
-impl<'s, T> Located<'s, T> {
-    pub fn new(value: T, span: Span<'s>) -> Self {
+impl<T> Located<T> {
+    pub fn new(value: T, span: Span) -> Self {
         Self { value, span }
     }
 
-    pub fn map<U, F: FnOnce(T) -> U>(self, f: F) -> Located<'s, U> {
+    pub fn map<U, F: FnOnce(T) -> U>(self, f: F) -> Located<U> {
         Located::new(f(self.value), self.span)
     }
 }
 
-impl<'s, T: std::fmt::Debug> std::fmt::Debug for Located<'s, T> {
+impl<T: std::fmt::Debug> std::fmt::Debug for Located<T> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "{}: {:?}", self.span.location(), self.value)
+        write!(f, "{:?}: {:?}", self.span, self.value)
     }
 }
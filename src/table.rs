@@ -1,4 +1,6 @@
-use std::{collections::HashMap, fmt::Debug};
+use std::{collections::HashMap, fmt::Debug, mem::discriminant};
+
+use crate::span::Span;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum PropertyType {
@@ -10,6 +12,10 @@ pub enum PropertyType {
 pub enum Type {
     Class,
     Property(Option<PropertyType>),
+    AnnotationProperty,
+    /// A name lexed as a class but used as an individual, such as a member
+    /// of `SameIndividual:`.
+    Individual,
     Literal,
 }
 
@@ -24,6 +30,8 @@ impl Debug for Type {
                 }
                 Ok(())
             }
+            Self::AnnotationProperty => write!(f, "AnnotationProperty"),
+            Self::Individual => write!(f, "Individual"),
             Self::Literal => write!(f, "Literal"),
         }
     }
@@ -33,11 +41,18 @@ impl Debug for Type {
 pub struct Symbol {
     type_: Type,
     id: String,
+    typed_at: Option<Span>,
+    annotations: Vec<String>,
 }
 
 impl Symbol {
     pub fn new(type_: Type, id: String) -> Self {
-        Self { type_, id }
+        Self {
+            type_,
+            id,
+            typed_at: None,
+            annotations: Vec::new(),
+        }
     }
 
     pub fn type_(&self) -> Type {
@@ -47,29 +62,46 @@ impl Symbol {
     pub fn id(&self) -> &str {
         &self.id
     }
+
+    /// Where the type of a property was first inferred, or where a class was
+    /// first used as an individual, possibly in another file.
+    pub fn typed_at(&self) -> Option<Span> {
+        self.typed_at
+    }
+
+    /// Annotations given in the frame of the symbol, rendered as in the source.
+    pub fn annotations(&self) -> &[String] {
+        &self.annotations
+    }
 }
 
+#[derive(Default)]
 pub struct SymbolTable {
     symbols: HashMap<usize, Symbol>,
 }
 
 impl SymbolTable {
     pub fn new() -> Self {
-        Self {
-            symbols: HashMap::new(),
-        }
+        Self::default()
     }
 
     pub fn get(&self, index: usize) -> Option<&Symbol> {
         self.symbols.get(&index)
     }
 
+    /// Looks a symbol up by its kind and id, so a literal such as `"Pizza"`
+    /// is a symbol apart from the class `Pizza`. Properties are looked up
+    /// whatever their inferred type, and classes whether or not they were
+    /// found to be individuals.
     pub fn get_or_insert(&mut self, type_: Type, id: String) -> usize {
-        if let Some(index) = self
-            .symbols
-            .iter()
-            .find_map(|(index, symbol)| (symbol.id() == id).then_some(index))
-        {
+        let kind = |type_: &Type| match type_ {
+            Type::Individual => discriminant(&Type::Class),
+            type_ => discriminant(type_),
+        };
+
+        if let Some(index) = self.symbols.iter().find_map(|(index, symbol)| {
+            (symbol.id() == id && kind(&symbol.type_) == kind(&type_)).then_some(index)
+        }) {
             *index
         } else {
             let index = self.symbols.len();
@@ -78,10 +110,11 @@ impl SymbolTable {
         }
     }
 
-    pub fn update_property_type(&mut self, index: usize, type_: PropertyType) -> bool {
+    pub fn update_property_type(&mut self, index: usize, type_: PropertyType, span: Span) -> bool {
         if let Some(symbol) = self.symbols.get_mut(&index) {
             if let Type::Property(None) = symbol.type_ {
                 symbol.type_ = Type::Property(Some(type_));
+                symbol.typed_at = Some(span);
                 true
             } else {
                 false
@@ -91,7 +124,65 @@ impl SymbolTable {
         }
     }
 
+    /// Records that a class is used as an individual, returning whether the
+    /// symbol is a class or already an individual.
+    pub fn update_individual(&mut self, index: usize, span: Span) -> bool {
+        match self.symbols.get_mut(&index) {
+            Some(symbol) if matches!(symbol.type_, Type::Class) => {
+                symbol.type_ = Type::Individual;
+                symbol.typed_at = Some(span);
+                true
+            }
+            Some(symbol) => matches!(symbol.type_, Type::Individual),
+            None => false,
+        }
+    }
+
+    pub fn annotate(&mut self, index: usize, annotation: String) {
+        if let Some(symbol) = self.symbols.get_mut(&index) {
+            symbol.annotations.push(annotation);
+        }
+    }
+
     pub fn symbols(&self) -> &HashMap<usize, Symbol> {
         &self.symbols
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::span::SourceMap;
+
+    #[test]
+    fn literals_are_apart_from_classes_and_properties() {
+        let mut table = SymbolTable::new();
+        let class = table.get_or_insert(Type::Class, "Pizza".to_string());
+        let literal = table.get_or_insert(Type::Literal, "Pizza".to_string());
+
+        assert_ne!(class, literal);
+        assert_eq!(table.get_or_insert(Type::Class, "Pizza".to_string()), class);
+    }
+
+    #[test]
+    fn individuals_keep_the_index_of_their_class() {
+        let mut map = SourceMap::new();
+        let file_id = map.add("test.txt".to_string(), "Banana".to_string());
+        let span = map.span(file_id);
+        let mut table = SymbolTable::new();
+        let index = table.get_or_insert(Type::Class, "Banana".to_string());
+
+        assert!(table.update_individual(index, span));
+        assert!(matches!(
+            table.get(index).unwrap().type_(),
+            Type::Individual
+        ));
+        assert_eq!(
+            table.get_or_insert(Type::Class, "Banana".to_string()),
+            index
+        );
+
+        let property = table.get_or_insert(Type::Property(None), "hasBase".to_string());
+        assert!(!table.update_individual(property, span));
+    }
+}
@@ -0,0 +1,827 @@
+use std::collections::{BTreeSet, HashMap};
+
+use crate::{
+    hierarchy::{self, Node},
+    parser::{Class, ClassDecl, Frame, ObjectDescription, Ontology, Property, PropertyDescription},
+    span::{Located, SourceMap},
+    table::SymbolTable,
+};
+
+/// Concept of the ALCQ description logic, in negation normal form.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Concept {
+    Top,
+    Bottom,
+    Named(Node),
+    Not(Node),
+    And(Vec<Concept>),
+    Or(Vec<Concept>),
+    /// `∃r.C`, with the property by its index in the symbol table.
+    Some(usize, Box<Concept>),
+    /// `∀r.C`
+    All(usize, Box<Concept>),
+    /// `≥n r.C`
+    Min(usize, usize, Box<Concept>),
+    /// `≤n r.C`
+    Max(usize, usize, Box<Concept>),
+}
+
+impl Concept {
+    /// The negation of the concept, in negation normal form.
+    pub fn negate(&self) -> Self {
+        match self {
+            Self::Top => Self::Bottom,
+            Self::Bottom => Self::Top,
+            Self::Named(node) => Self::Not(*node),
+            Self::Not(node) => Self::Named(*node),
+            Self::And(concepts) => Self::Or(concepts.iter().map(Self::negate).collect()),
+            Self::Or(concepts) => Self::And(concepts.iter().map(Self::negate).collect()),
+            Self::Some(role, filler) => Self::All(*role, Box::new(filler.negate())),
+            Self::All(role, filler) => Self::Some(*role, Box::new(filler.negate())),
+            Self::Min(0, _, _) => Self::Bottom,
+            Self::Min(n, role, filler) => Self::Max(n - 1, *role, filler.clone()),
+            Self::Max(n, role, filler) => Self::Min(n + 1, *role, filler.clone()),
+        }
+    }
+}
+
+/// The ALCQ concept of a class description.
+///
+/// Individuals, in enumerations and `VALUE` restrictions, are read as
+/// classes of their own, and data restrictions as `Thing`, so answers about
+/// them are approximations.
+pub fn concept(class: &Class) -> Concept {
+    match class {
+        Class::Identifier(class) => Concept::Named(Node::Named(class.index)),
+        Class::Defined(super_class, properties) => intersection(super_class.index, properties),
+        Class::Enumerated(classes) | Class::Union(classes) => Concept::Or(
+            classes
+                .iter()
+                .map(|class| Concept::Named(Node::Named(class.index)))
+                .collect(),
+        ),
+        Class::Complement(class) => concept(class).negate(),
+    }
+}
+
+fn intersection(super_class: usize, properties: &[Located<Property>]) -> Concept {
+    let mut concepts = vec![Concept::Named(Node::Named(super_class))];
+    concepts.extend(
+        properties
+            .iter()
+            .map(|property| restriction(&property.value)),
+    );
+    Concept::And(concepts)
+}
+
+fn flatten(concepts: &[Concept], conjuncts: &mut Vec<Concept>) {
+    for concept in concepts {
+        match concept {
+            Concept::And(concepts) => flatten(concepts, conjuncts),
+            concept => conjuncts.push(concept.clone()),
+        }
+    }
+}
+
+fn restriction(property: &Property) -> Concept {
+    let role = property.index;
+
+    match &property.description {
+        PropertyDescription::Object(description) => match description {
+            ObjectDescription::Some(class) => Concept::Some(role, Box::new(concept(class))),
+            ObjectDescription::Only(class) => Concept::All(role, Box::new(concept(class))),
+            ObjectDescription::Value(individual) => Concept::Some(
+                role,
+                Box::new(Concept::Named(Node::Named(individual.index))),
+            ),
+            ObjectDescription::Min(n, class) => Concept::Min(*n, role, Box::new(concept(class))),
+            ObjectDescription::Max(n, class) => Concept::Max(*n, role, Box::new(concept(class))),
+            ObjectDescription::Exactly(n, class) => Concept::And(vec![
+                Concept::Min(*n, role, Box::new(concept(class))),
+                Concept::Max(*n, role, Box::new(concept(class))),
+            ]),
+        },
+        PropertyDescription::Data(_) => Concept::Top,
+    }
+}
+
+/// Satisfiability and subsumption checker for the axioms of an ontology,
+/// by the tableau algorithm for ALCQ.
+///
+/// Property chains and disjoint properties are outside ALCQ and ignored.
+pub struct Tableau {
+    /// `A ⊑ C` axioms, applied only to individuals labelled with `A`.
+    absorbed: HashMap<Node, Vec<Concept>>,
+    /// `¬C ⊔ D` for every other `C ⊑ D`, added to every individual.
+    general: Vec<Concept>,
+    nodes: Vec<Node>,
+}
+
+// an individual of the completion tree
+#[derive(Clone)]
+struct Individual {
+    label: BTreeSet<Concept>,
+    parent: Option<(usize, usize)>,
+    alive: bool,
+}
+
+// a completion tree, grown until it is complete or has a clash
+#[derive(Clone)]
+struct Tree {
+    individuals: Vec<Individual>,
+    distinct: BTreeSet<(usize, usize)>,
+    // at-least restrictions already expanded at an individual
+    expanded: BTreeSet<(usize, Concept)>,
+}
+
+enum Choice {
+    Add(usize, Concept),
+    Merge(usize, usize),
+}
+
+impl Tableau {
+    pub fn new<'o>(ontologies: impl IntoIterator<Item = &'o Ontology>) -> Self {
+        let mut tableau = Self {
+            absorbed: HashMap::new(),
+            general: Vec::new(),
+            nodes: Vec::new(),
+        };
+
+        for ontology in ontologies {
+            for frame in &ontology.frames {
+                tableau.frame(&frame.value);
+            }
+        }
+
+        tableau
+    }
+
+    fn frame(&mut self, frame: &Frame) {
+        match frame {
+            Frame::Decl { decl, .. } => {
+                let node = Node::Anonymous(decl.span);
+                let class = Concept::Named(node);
+                self.nodes.push(node);
+
+                match &decl.value {
+                    ClassDecl::Defined(super_class, properties) => {
+                        self.nodes.push(Node::Named(super_class.index));
+                        let concept = intersection(super_class.index, properties);
+                        self.subsume(&class, &concept);
+                        self.subsume(&concept, &class);
+                    }
+                    ClassDecl::Primitive(super_class, properties) => {
+                        self.nodes.push(Node::Named(super_class.index));
+                        let concept = intersection(super_class.index, properties);
+                        self.subsume(&class, &concept);
+                    }
+                    ClassDecl::Enumerated(classes) | ClassDecl::Union(classes) => {
+                        let concept = Concept::Or(
+                            classes
+                                .iter()
+                                .map(|class| Concept::Named(Node::Named(class.index)))
+                                .collect(),
+                        );
+                        self.subsume(&class, &concept);
+                        self.subsume(&concept, &class);
+                    }
+                }
+            }
+            Frame::Class {
+                class,
+                super_classes,
+                ..
+            } => {
+                let node = Node::Named(class.index);
+                self.nodes.push(node);
+
+                for super_class in super_classes {
+                    self.subsume(&Concept::Named(node), &concept(&super_class.value));
+                }
+            }
+            Frame::EquivalentClasses(misc) => {
+                let members = misc.members.iter().map(concept).collect::<Vec<_>>();
+
+                for (i, sub) in members.iter().enumerate() {
+                    for (j, sup) in members.iter().enumerate() {
+                        if i != j {
+                            self.subsume(sub, sup);
+                        }
+                    }
+                }
+            }
+            Frame::DisjointClasses(misc) => {
+                let members = misc.members.iter().map(concept).collect::<Vec<_>>();
+
+                for (i, a) in members.iter().enumerate() {
+                    for b in &members[i + 1..] {
+                        self.subsume(&Concept::And(vec![a.clone(), b.clone()]), &Concept::Bottom);
+                    }
+                }
+            }
+            Frame::DifferentIndividuals(misc) => {
+                for (i, a) in misc.members.iter().enumerate() {
+                    for b in &misc.members[i + 1..] {
+                        let a = Concept::Named(Node::Named(a.index));
+                        let b = Concept::Named(Node::Named(b.index));
+                        self.subsume(&Concept::And(vec![a, b]), &Concept::Bottom);
+                    }
+                }
+            }
+            Frame::AnnotationProperty { .. }
+            | Frame::ObjectProperty { .. }
+            | Frame::DataProperty { .. }
+            | Frame::DisjointProperties(_)
+            | Frame::SameIndividual(_) => {}
+        }
+    }
+
+    // absorbs `sub ⊑ sup` into the axioms of a named class where possible,
+    // so that it is only applied to the individuals that need it
+    fn subsume(&mut self, sub: &Concept, sup: &Concept) {
+        match sub {
+            Concept::Top => self.general.push(sup.clone()),
+            Concept::Named(node) => self.absorbed.entry(*node).or_default().push(sup.clone()),
+            Concept::Or(concepts) => {
+                for concept in concepts {
+                    self.subsume(concept, sup);
+                }
+            }
+            Concept::And(concepts) => {
+                let mut conjuncts = Vec::new();
+                flatten(concepts, &mut conjuncts);
+
+                match conjuncts
+                    .iter()
+                    .position(|concept| matches!(concept, Concept::Named(_)))
+                {
+                    // `A ⊓ C ⊑ D` becomes `A ⊑ ¬C ⊔ D`
+                    Some(i) => {
+                        let named = conjuncts.remove(i);
+                        let mut concepts =
+                            conjuncts.iter().map(Concept::negate).collect::<Vec<_>>();
+                        concepts.push(sup.clone());
+                        self.subsume(&named, &Concept::Or(concepts));
+                    }
+                    None => self
+                        .general
+                        .push(Concept::Or(vec![sub.negate(), sup.clone()])),
+                }
+            }
+            _ => self
+                .general
+                .push(Concept::Or(vec![sub.negate(), sup.clone()])),
+        }
+    }
+
+    /// Whether some individual can be an instance of `concept` without
+    /// contradicting the axioms.
+    pub fn is_satisfiable(&self, concept: &Concept) -> bool {
+        let mut tree = Tree {
+            individuals: Vec::new(),
+            distinct: BTreeSet::new(),
+            expanded: BTreeSet::new(),
+        };
+
+        tree.add_individual(None, [concept.clone()], self);
+        tree.expand(self)
+    }
+
+    /// Whether the class, named or described by a declaration, can have
+    /// instances.
+    pub fn is_class_satisfiable(&self, node: Node) -> bool {
+        self.is_satisfiable(&Concept::Named(node))
+    }
+
+    /// Whether every instance of `sub` is an instance of `sup`.
+    pub fn subsumes(&self, sup: &Concept, sub: &Concept) -> bool {
+        !self.is_satisfiable(&Concept::And(vec![sub.clone(), sup.negate()]))
+    }
+
+    /// Whether the axioms can hold together at all.
+    pub fn is_consistent(&self) -> bool {
+        self.is_satisfiable(&Concept::Top)
+    }
+
+    /// Classes with axioms of their own, in order of declaration.
+    pub fn classes(&self) -> Vec<Node> {
+        let mut classes = self.nodes.clone();
+        let mut seen = BTreeSet::new();
+        classes.retain(|node| seen.insert(*node));
+        classes
+    }
+
+    /// Classes with axioms of their own that can have no instances.
+    pub fn unsatisfiable(&self) -> Vec<Node> {
+        self.classes()
+            .into_iter()
+            .filter(|node| !self.is_class_satisfiable(*node))
+            .collect()
+    }
+
+    /// Whether the axioms are consistent, followed by the unsatisfiable
+    /// classes, one per line.
+    pub fn render(&self, map: &SourceMap, table: &SymbolTable) -> String {
+        if !self.is_consistent() {
+            return "Inconsistent\n".to_string();
+        }
+
+        let mut report = "Consistent\n".to_string();
+        let unsatisfiable = self.unsatisfiable();
+
+        if !unsatisfiable.is_empty() {
+            report.push_str("Unsatisfiable:\n");
+
+            for node in unsatisfiable {
+                report.push_str(&format!("  {}\n", hierarchy::name(node, map, table)));
+            }
+        }
+
+        report
+    }
+}
+
+impl Tree {
+    fn add_individual(
+        &mut self,
+        parent: Option<(usize, usize)>,
+        concepts: impl IntoIterator<Item = Concept>,
+        tableau: &Tableau,
+    ) -> usize {
+        let mut label = concepts.into_iter().collect::<BTreeSet<_>>();
+        label.extend(tableau.general.iter().cloned());
+
+        self.individuals.push(Individual {
+            label,
+            parent,
+            alive: true,
+        });
+        self.individuals.len() - 1
+    }
+
+    fn successors(&self, x: usize, role: usize) -> Vec<usize> {
+        (0..self.individuals.len())
+            .filter(|&y| self.individuals[y].alive && self.individuals[y].parent == Some((x, role)))
+            .collect()
+    }
+
+    fn are_distinct(&self, x: usize, y: usize) -> bool {
+        self.distinct.contains(&(x.min(y), x.max(y)))
+    }
+
+    // an individual whose label is contained in the label of an ancestor
+    // needs no successors of its own, it can reuse the ancestor's
+    fn is_blocked(&self, x: usize) -> bool {
+        let mut ancestor = self.individuals[x].parent;
+
+        while let Some((y, _)) = ancestor {
+            if self.individuals[x]
+                .label
+                .is_subset(&self.individuals[y].label)
+            {
+                return true;
+            }
+
+            ancestor = self.individuals[y].parent;
+        }
+
+        false
+    }
+
+    fn expand(&mut self, tableau: &Tableau) -> bool {
+        loop {
+            self.propagate(tableau);
+
+            if self.has_clash() {
+                return false;
+            }
+
+            if let Some(choices) = self.choices() {
+                return choices.into_iter().any(|choice| {
+                    let mut tree = self.clone();
+                    tree.apply(choice);
+                    tree.expand(tableau)
+                });
+            }
+
+            if !self.generate(tableau) {
+                return true;
+            }
+        }
+    }
+
+    // deterministic rules: intersections, absorbed axioms and universal
+    // restrictions, until nothing changes
+    fn propagate(&mut self, tableau: &Tableau) {
+        let mut changed = true;
+
+        while changed {
+            changed = false;
+
+            for x in 0..self.individuals.len() {
+                if !self.individuals[x].alive {
+                    continue;
+                }
+
+                let mut added = Vec::new();
+
+                for concept in &self.individuals[x].label {
+                    match concept {
+                        Concept::And(concepts) => added.extend(concepts.iter().cloned()),
+                        Concept::Named(node) => {
+                            added.extend(tableau.absorbed.get(node).into_iter().flatten().cloned())
+                        }
+                        _ => {}
+                    }
+                }
+
+                for concept in added {
+                    changed |= self.individuals[x].label.insert(concept);
+                }
+
+                let universals = self.individuals[x]
+                    .label
+                    .iter()
+                    .filter_map(|concept| match concept {
+                        Concept::All(role, filler) => Some((*role, filler.as_ref().clone())),
+                        _ => None,
+                    })
+                    .collect::<Vec<_>>();
+
+                for (role, filler) in universals {
+                    for y in self.successors(x, role) {
+                        changed |= self.individuals[y].label.insert(filler.clone());
+                    }
+                }
+            }
+        }
+    }
+
+    fn has_clash(&self) -> bool {
+        self.individuals
+            .iter()
+            .enumerate()
+            .filter(|(_, individual)| individual.alive)
+            .any(|(x, individual)| {
+                individual.label.iter().any(|concept| match concept {
+                    Concept::Bottom => true,
+                    Concept::Not(node) => individual.label.contains(&Concept::Named(*node)),
+                    // more successors than allowed, none of which can be merged
+                    Concept::Max(n, role, filler) => {
+                        let successors = self.successors(x, *role);
+                        let successors = successors
+                            .iter()
+                            .filter(|&&y| self.individuals[y].label.contains(filler))
+                            .collect::<Vec<_>>();
+
+                        successors.len() > *n
+                            && successors.iter().enumerate().all(|(i, &&y)| {
+                                successors[i + 1..]
+                                    .iter()
+                                    .all(|&&z| self.are_distinct(y, z))
+                            })
+                    }
+                    _ => false,
+                })
+            })
+    }
+
+    // nondeterministic rules: unions, the choose rule for at-most
+    // restrictions, and merging successors over an at-most restriction
+    fn choices(&self) -> Option<Vec<Choice>> {
+        for (x, individual) in self.individuals.iter().enumerate() {
+            if !individual.alive {
+                continue;
+            }
+
+            for concept in &individual.label {
+                match concept {
+                    Concept::Or(concepts)
+                        if !concepts.iter().any(|c| individual.label.contains(c)) =>
+                    {
+                        // disjuncts that clash right away are not worth a branch
+                        return Some(
+                            concepts
+                                .iter()
+                                .filter(|concept| match concept {
+                                    Concept::Bottom => false,
+                                    Concept::Named(_) | Concept::Not(_) => {
+                                        !individual.label.contains(&concept.negate())
+                                    }
+                                    _ => true,
+                                })
+                                .map(|concept| Choice::Add(x, concept.clone()))
+                                .collect(),
+                        );
+                    }
+                    Concept::Max(n, role, filler) => {
+                        let successors = self.successors(x, *role);
+                        let negated = filler.negate();
+
+                        for &y in &successors {
+                            let label = &self.individuals[y].label;
+
+                            if !label.contains(filler) && !label.contains(&negated) {
+                                return Some(vec![
+                                    Choice::Add(y, filler.as_ref().clone()),
+                                    Choice::Add(y, negated),
+                                ]);
+                            }
+                        }
+
+                        let successors = successors
+                            .into_iter()
+                            .filter(|&y| self.individuals[y].label.contains(filler))
+                            .collect::<Vec<_>>();
+
+                        if successors.len() > *n {
+                            let mut merges = Vec::new();
+
+                            for (i, &y) in successors.iter().enumerate() {
+                                for &z in &successors[i + 1..] {
+                                    if !self.are_distinct(y, z) {
+                                        merges.push(Choice::Merge(y, z));
+                                    }
+                                }
+                            }
+
+                            return Some(merges);
+                        }
+                    }
+                    _ => {}
+                }
+            }
+        }
+
+        None
+    }
+
+    fn apply(&mut self, choice: Choice) {
+        match choice {
+            Choice::Add(x, concept) => {
+                self.individuals[x].label.insert(concept);
+            }
+            Choice::Merge(y, z) => {
+                // z goes into y, taking its label, successors and
+                // distinctions along
+                let label = std::mem::take(&mut self.individuals[z].label);
+                self.individuals[y].label.extend(label);
+                self.individuals[z].alive = false;
+
+                for individual in &mut self.individuals {
+                    if let Some((parent, role)) = individual.parent {
+                        if parent == z {
+                            individual.parent = Some((y, role));
+                        }
+                    }
+                }
+
+                self.distinct = std::mem::take(&mut self.distinct)
+                    .into_iter()
+                    .map(|(a, b)| {
+                        let a = if a == z { y } else { a };
+                        let b = if b == z { y } else { b };
+                        (a.min(b), a.max(b))
+                    })
+                    .collect();
+            }
+        }
+    }
+
+    // generating rules: successors for existential and at-least
+    // restrictions of individuals that are not blocked
+    fn generate(&mut self, tableau: &Tableau) -> bool {
+        for x in 0..self.individuals.len() {
+            if !self.individuals[x].alive || self.is_blocked(x) {
+                continue;
+            }
+
+            for concept in self.individuals[x].label.clone() {
+                match &concept {
+                    Concept::Some(role, filler) => {
+                        let satisfied = self
+                            .successors(x, *role)
+                            .iter()
+                            .any(|&y| self.individuals[y].label.contains(filler));
+
+                        if !satisfied {
+                            self.add_individual(Some((x, *role)), [*filler.clone()], tableau);
+                            return true;
+                        }
+                    }
+                    Concept::Min(n, role, filler)
+                        if *n > 0 && self.expanded.insert((x, concept.clone())) =>
+                    {
+                        let created = (0..*n)
+                            .map(|_| {
+                                self.add_individual(Some((x, *role)), [*filler.clone()], tableau)
+                            })
+                            .collect::<Vec<_>>();
+
+                        for (i, &y) in created.iter().enumerate() {
+                            for &z in &created[i + 1..] {
+                                self.distinct.insert((y.min(z), y.max(z)));
+                            }
+                        }
+
+                        return true;
+                    }
+                    _ => {}
+                }
+            }
+        }
+
+        false
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{lexer, parser};
+
+    const LOOP: Node = Node::Named(0);
+    const C: Node = Node::Named(1);
+    const HAS_PART: usize = 2;
+
+    fn tableau(absorbed: impl IntoIterator<Item = (Node, Vec<Concept>)>) -> Tableau {
+        Tableau {
+            absorbed: absorbed.into_iter().collect(),
+            general: Vec::new(),
+            nodes: Vec::new(),
+        }
+    }
+
+    // the tableau of an ontology, with its symbol table and frames
+    fn parse(text: &str) -> (SymbolTable, Ontology, Tableau) {
+        let mut map = SourceMap::new();
+        let mut table = SymbolTable::new();
+        let file_id = map.add("test.txt".to_string(), text.to_string());
+
+        let Ok(tokens) = lexer::parse(&map, file_id, &mut table) else {
+            panic!("could not lex {:?}", text);
+        };
+        let Ok(ontology) = parser::parse(&tokens, &mut table) else {
+            panic!("could not parse {:?}", text);
+        };
+
+        let tableau = Tableau::new([&ontology]);
+        (table, ontology, tableau)
+    }
+
+    // the index of a symbol of the table, by name
+    fn index(table: &SymbolTable, name: &str) -> usize {
+        table
+            .symbols()
+            .iter()
+            .find(|(_, symbol)| symbol.id() == name)
+            .map(|(index, _)| *index)
+            .unwrap_or_else(|| panic!("no symbol {:?}", name))
+    }
+
+    fn named(table: &SymbolTable, name: &str) -> Concept {
+        Concept::Named(Node::Named(index(table, name)))
+    }
+
+    #[test]
+    fn min_above_max_clashes() {
+        let tableau = tableau([]);
+        let min = Concept::Min(3, HAS_PART, Box::new(Concept::Named(C)));
+        let max = Concept::Max(2, HAS_PART, Box::new(Concept::Named(C)));
+
+        assert!(!tableau.is_satisfiable(&Concept::And(vec![min.clone(), max])));
+
+        let max = Concept::Max(3, HAS_PART, Box::new(Concept::Named(C)));
+        assert!(tableau.is_satisfiable(&Concept::And(vec![min, max])));
+    }
+
+    #[test]
+    fn terminates_on_a_cyclic_axiom() {
+        // Loop ⊑ ∃hasPart.Loop
+        let tableau = tableau([(
+            LOOP,
+            vec![Concept::Some(HAS_PART, Box::new(Concept::Named(LOOP)))],
+        )]);
+
+        assert!(tableau.is_class_satisfiable(LOOP));
+        assert!(tableau.subsumes(
+            &Concept::Some(
+                HAS_PART,
+                Box::new(Concept::Some(HAS_PART, Box::new(Concept::Top)))
+            ),
+            &Concept::Named(LOOP),
+        ));
+    }
+
+    #[test]
+    fn terminates_on_a_cyclic_general_axiom() {
+        // ⊤ ⊑ ≥2 hasPart.⊤, which every individual must repeat
+        let mut tableau = tableau([]);
+        tableau
+            .general
+            .push(Concept::Min(2, HAS_PART, Box::new(Concept::Top)));
+
+        assert!(tableau.is_consistent());
+        assert!(!tableau.is_satisfiable(&Concept::Max(1, HAS_PART, Box::new(Concept::Top))));
+    }
+
+    #[test]
+    fn defined_declarations_are_equivalences() {
+        let (table, ontology, tableau) = parse(
+            "Pizza AND (hasTopping SOME Cheese)\n\
+             Pizza hasTopping SOME Ham\n",
+        );
+        let [defined, primitive] =
+            [0, 1].map(|i| Concept::Named(Node::Anonymous(ontology.frames[i].span)));
+        let pizza = named(&table, "Pizza");
+        let has_topping = index(&table, "hasTopping");
+
+        let cheesy = Concept::And(vec![
+            pizza.clone(),
+            Concept::Some(has_topping, Box::new(named(&table, "Cheese"))),
+        ]);
+        assert!(tableau.subsumes(&pizza, &defined));
+        assert!(tableau.subsumes(&defined, &cheesy));
+
+        let ham = Concept::And(vec![
+            pizza.clone(),
+            Concept::Some(has_topping, Box::new(named(&table, "Ham"))),
+        ]);
+        assert!(tableau.subsumes(&ham, &primitive));
+        assert!(!tableau.subsumes(&primitive, &ham));
+    }
+
+    #[test]
+    fn disjoint_classes_have_no_common_subclass() {
+        let (table, _, tableau) = parse(
+            "DisjointClasses: Cheese, Meat\n\
+             Class: Salami SubClassOf: Cheese, Meat\n\
+             Class: Bacon SubClassOf: Meat\n",
+        );
+
+        let salami = Node::Named(index(&table, "Salami"));
+        let bacon = Node::Named(index(&table, "Bacon"));
+        assert!(!tableau.is_class_satisfiable(salami));
+        assert!(tableau.is_class_satisfiable(bacon));
+    }
+
+    #[test]
+    fn equivalent_classes_subsume_each_other() {
+        let (table, _, tableau) = parse(
+            "EquivalentClasses: Mozzarella, Cheese\n\
+             Class: Buffalo SubClassOf: Mozzarella\n",
+        );
+        let cheese = named(&table, "Cheese");
+        let mozzarella = named(&table, "Mozzarella");
+
+        assert!(tableau.subsumes(&cheese, &named(&table, "Buffalo")));
+        assert!(tableau.subsumes(&mozzarella, &cheese));
+        assert!(!tableau.subsumes(&named(&table, "Buffalo"), &cheese));
+    }
+
+    #[test]
+    fn values_of_different_individuals_clash() {
+        let (table, _, tableau) = parse(
+            "DifferentIndividuals: Anna, Bruno\n\
+             Class: Lonely SubClassOf: (Person AND (hasFriend VALUE Anna)), \
+             (Person AND (hasFriend ONLY Bruno))\n\
+             Class: Friendly SubClassOf: (Person AND (hasFriend VALUE Anna))\n",
+        );
+
+        let lonely = Node::Named(index(&table, "Lonely"));
+        let friendly = Node::Named(index(&table, "Friendly"));
+        assert!(!tableau.is_class_satisfiable(lonely));
+        assert!(tableau.is_class_satisfiable(friendly));
+    }
+
+    #[test]
+    fn exactly_is_at_least_and_at_most() {
+        let (table, _, tableau) =
+            parse("Class: Trio SubClassOf: (Pizza AND (hasTopping EXACTLY 3 Cheese))\n");
+        let trio = named(&table, "Trio");
+        let has_topping = index(&table, "hasTopping");
+        let cheese = Box::new(named(&table, "Cheese"));
+
+        assert!(tableau.subsumes(&Concept::Min(2, has_topping, cheese.clone()), &trio));
+        assert!(tableau.subsumes(&Concept::Max(3, has_topping, cheese.clone()), &trio));
+        assert!(!tableau.is_satisfiable(&Concept::And(vec![
+            trio,
+            Concept::Min(4, has_topping, cheese),
+        ])));
+    }
+
+    #[test]
+    fn data_restrictions_are_thing() {
+        let (table, _, tableau) = parse(
+            "Class: Light SubClassOf: (Pizza AND (hasCalories SOME integer[<\"100\"]))\n\
+             Class: Heavy SubClassOf: (Pizza AND (hasCalories SOME integer[>\"1000\"]))\n",
+        );
+
+        // the ranges are disjoint, but only as data, which is not looked at
+        assert!(tableau.is_satisfiable(&Concept::And(vec![
+            named(&table, "Light"),
+            named(&table, "Heavy"),
+        ])));
+        assert!(tableau.subsumes(&named(&table, "Pizza"), &named(&table, "Light")));
+    }
+}